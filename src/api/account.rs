@@ -0,0 +1,336 @@
+//! Lists of the logged in account, see [`BahamutClient::login`].
+
+use scraper::{ElementRef, Html};
+use url::Url;
+
+use super::{
+    board::BoardPost,
+    get_document,
+    link::{
+        boards_from_rows, posts_from_html, posts_from_rows, rows, section, BOARD_LINKS, POST_LINKS,
+    },
+    parse::Field,
+    search::SearchResult,
+    BahamutClient, Error, ParseError, Timestamp, WebSite,
+};
+
+/// Subscribed boards (訂閱看板)
+static SUBSCRIBED_BOARDS_PATH: &str = "myBoard.php";
+/// Followed threads (追蹤文章)
+static FOLLOWED_POSTS_PATH: &str = "myTrace.php";
+/// Saved articles (收藏文章)
+static SAVED_POSTS_PATH: &str = "myCollect.php";
+/// Notification feed (通知)
+static NOTIFICATIONS_PATH: &str = "myNotify.php";
+
+/// Rows of every account list, the forum list rows or the items of a plain
+/// list or table. A row nested in another belongs to it.
+const ROWS: Field = Field::new(
+    "Account::rows",
+    ".b-list__row, ul > li, ol > li, tbody > tr",
+);
+
+/// Texts shown instead of an empty list
+const EMPTY_MARKERS: [&str; 5] = ["目前沒有", "尚未", "沒有任何", "查無", "無資料"];
+
+/// Where a list is on its page, by selector or else under one of the titles.
+struct List {
+    field: Field,
+    titles: &'static [&'static str],
+    links: Field,
+}
+
+const BOARDS: List = List {
+    field: Field::new(
+        "Account::boards",
+        "#my-board, .my-board, [class*=\"myboard\"]",
+    ),
+    titles: &["訂閱看板", "我的看板"],
+    links: Field::new("Account::boards", BOARD_LINKS),
+};
+
+const FOLLOWED: List = List {
+    field: Field::new("Account::followed", "[class*=\"trace\"]"),
+    titles: &["追蹤文章", "追蹤"],
+    links: Field::new("Account::followed", POST_LINKS),
+};
+
+const SAVED: List = List {
+    field: Field::new("Account::saved", "[class*=\"collect\"]"),
+    titles: &["收藏文章", "收藏"],
+    links: Field::new("Account::saved", POST_LINKS),
+};
+
+const NOTIFICATIONS: List = List {
+    field: Field::new(
+        "Account::notifications",
+        "[class*=\"notify\"], [class*=\"notice\"]",
+    ),
+    titles: &["通知"],
+    links: ROWS,
+};
+
+/// An entry of the notification feed.
+#[derive(Clone)]
+pub struct Notification {
+    /// Text of the notification without its time, as in `xxx 回覆了你的文章`
+    pub text: String,
+    pub time: Timestamp,
+    /// Where the notification links to, if anywhere
+    pub url: Option<String>,
+    /// The linked thread, only the id, title, url and board are known
+    pub post: Option<BoardPost>,
+    /// Fields which can't be read and were left to their default
+    pub parse_errors: Vec<ParseError>,
+}
+
+impl Notification {
+    const TIME: Field = Field::new(
+        "Notification::time",
+        "time, [class*=\"time\"], [class*=\"date\"]",
+    );
+    const URL: Field = Field::new("Notification::url", "a[href]");
+
+    fn from_html(row: &ElementRef, base: &Url) -> Notification {
+        let mut parse_errors = vec![];
+
+        let time_text = Notification::TIME
+            .text(row)
+            .map_err(|e| parse_errors.push(e))
+            .unwrap_or_default();
+
+        let text = row.text().collect::<String>();
+        let text = text
+            .replacen(time_text.as_str(), "", 1)
+            .split_whitespace()
+            .collect::<Vec<&str>>()
+            .join(" ");
+
+        let url = Notification::URL
+            .first(row)
+            .and_then(|a| Notification::URL.attr(&a, "href"))
+            .and_then(|href| Notification::URL.url(base, href))
+            .map(|url| url.to_string())
+            .ok();
+
+        let post = posts_from_html(row, base, "Notification::post")
+            .map_err(|e| parse_errors.push(e))
+            .ok()
+            .and_then(|posts| posts.into_iter().next());
+
+        Notification {
+            text,
+            time: Timestamp::parse(time_text.as_ref()),
+            url,
+            post,
+            parse_errors,
+        }
+    }
+}
+
+/// Pages of the logged in account.
+///
+/// Every call fails with [`Error::Auth`] when the session isn't logged in
+/// or the forum asks to log in again.
+pub struct Account;
+
+impl Account {
+    pub async fn get_subscribed_boards(client: &BahamutClient) -> Result<Vec<SearchResult>, Error> {
+        let web = Account::get_account_page(client, SUBSCRIBED_BOARDS_PATH).await?;
+        Account::try_boards_from_html(&web.document, &web.url)
+    }
+
+    pub async fn get_followed_posts(client: &BahamutClient) -> Result<Vec<BoardPost>, Error> {
+        let web = Account::get_account_page(client, FOLLOWED_POSTS_PATH).await?;
+        Account::try_posts_from_html(&web.document, &web.url, &FOLLOWED)
+    }
+
+    pub async fn get_saved_posts(client: &BahamutClient) -> Result<Vec<BoardPost>, Error> {
+        let web = Account::get_account_page(client, SAVED_POSTS_PATH).await?;
+        Account::try_posts_from_html(&web.document, &web.url, &SAVED)
+    }
+
+    /// Every notification, including the ones without a link.
+    pub async fn get_notifications(client: &BahamutClient) -> Result<Vec<Notification>, Error> {
+        let web = Account::get_account_page(client, NOTIFICATIONS_PATH).await?;
+        Account::try_notifications_from_html(&web.document, &web.url)
+    }
+
+    /// Parse a saved subscribed boards page.
+    pub fn parse_boards(html: &str, url: &Url) -> Result<Vec<SearchResult>, Error> {
+        Account::try_boards_from_html(&Html::parse_document(html), url)
+    }
+
+    /// Parse a saved followed threads page.
+    pub fn parse_followed_posts(html: &str, url: &Url) -> Result<Vec<BoardPost>, Error> {
+        Account::try_posts_from_html(&Html::parse_document(html), url, &FOLLOWED)
+    }
+
+    /// Parse a saved articles page.
+    pub fn parse_saved_posts(html: &str, url: &Url) -> Result<Vec<BoardPost>, Error> {
+        Account::try_posts_from_html(&Html::parse_document(html), url, &SAVED)
+    }
+
+    /// Parse a saved notifications page.
+    pub fn parse_notifications(html: &str, url: &Url) -> Result<Vec<Notification>, Error> {
+        Account::try_notifications_from_html(&Html::parse_document(html), url)
+    }
+
+    async fn get_account_page(client: &BahamutClient, path: &str) -> Result<WebSite, Error> {
+        if !client.session().is_logged_in() {
+            return Err(Error::Auth("not logged in".to_string()));
+        }
+
+        let url = client.url(path);
+        let document = get_document(client, &url).await?;
+        Ok(WebSite { url, document })
+    }
+
+    /// An expired session gets the login form instead of the list.
+    fn is_login_page(document: &Html) -> bool {
+        Field::new("Account::login", "input[type=\"password\"]")
+            .first(&document.root_element())
+            .is_ok()
+    }
+
+    /// The page says the list is empty rather than showing no list at all,
+    /// which is what a changed layout or a wrong path looks like.
+    fn is_empty_list(elm: &ElementRef) -> bool {
+        let text = elm.text().collect::<String>();
+        EMPTY_MARKERS.iter().any(|marker| text.contains(marker))
+    }
+
+    /// Rows of `list`, none only when the page shows it's empty.
+    fn rows<'a>(document: &'a Html, list: &List) -> Result<Vec<ElementRef<'a>>, Error> {
+        if Account::is_login_page(document) {
+            return Err(Error::Auth("session expired".to_string()));
+        }
+
+        let root = document.root_element();
+        let section = match section(document, list.field, list.titles, list.links) {
+            Ok(section) => section,
+            Err(_) if Account::is_empty_list(&root) => return Ok(vec![]),
+            Err(e) => return Err(e.into()),
+        };
+
+        match rows(&section, ROWS) {
+            Err(_) if Account::is_empty_list(&section) => Ok(vec![]),
+            res => Ok(res?),
+        }
+    }
+
+    fn try_boards_from_html(document: &Html, url: &Url) -> Result<Vec<SearchResult>, Error> {
+        let rows = Account::rows(document, &BOARDS)?;
+        Ok(boards_from_rows(&rows, url, "Account::boards")?)
+    }
+
+    fn try_posts_from_html(
+        document: &Html,
+        url: &Url,
+        list: &List,
+    ) -> Result<Vec<BoardPost>, Error> {
+        let rows = Account::rows(document, list)?;
+        Ok(posts_from_rows(&rows, url, "Account::posts")?)
+    }
+
+    fn try_notifications_from_html(document: &Html, url: &Url) -> Result<Vec<Notification>, Error> {
+        let notifications = Account::rows(document, &NOTIFICATIONS)?
+            .iter()
+            .map(|row| Notification::from_html(row, url))
+            .filter(|notification| !notification.text.is_empty())
+            .collect();
+
+        Ok(notifications)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NAV: &str = r#"<div class="BH-menu"><ul>
+<li><a href="B.php?bsn=1">nav board</a></li>
+<li><a href="C.php?bsn=1&amp;snA=1">nav thread</a></li></ul></div>"#;
+
+    fn page(content: &str) -> String {
+        format!("<html><body>{}{}</body></html>", NAV, content)
+    }
+
+    fn url(path: &str) -> Url {
+        Url::parse("https://forum.gamer.com.tw/")
+            .unwrap()
+            .join(path)
+            .unwrap()
+    }
+
+    #[test]
+    fn posts_from_their_rows() {
+        let html = page(
+            r#"<div class="BH-lbox"><h1>追蹤文章</h1><table class="b-list"><tbody>
+<tr class="b-list__row"><td><a href="C.php?bsn=60076&amp;snA=10">追蹤一</a>
+<a href="C.php?bsn=60076&amp;snA=10&amp;tnum=5">最新回覆</a></td></tr>
+<tr class="b-list__row"><td><a href="C.php?bsn=36730&amp;snA=20">追蹤二</a></td></tr>
+</tbody></table></div>"#,
+        );
+        let posts =
+            Account::parse_followed_posts(html.as_ref(), &url(FOLLOWED_POSTS_PATH)).unwrap();
+
+        assert_eq!(
+            posts
+                .iter()
+                .map(|p| p.title.as_ref())
+                .collect::<Vec<&str>>(),
+            vec!["追蹤一", "追蹤二"]
+        );
+    }
+
+    #[test]
+    fn boards_from_their_rows() {
+        let html = page(
+            r#"<div class="my-board"><ul>
+<li><a href="B.php?bsn=60076">場外休憩區</a> <a href="B.php?bsn=60076&amp;subbsn=1">子板</a></li>
+<li><a href="B.php?bsn=36730">原神</a></li></ul></div>"#,
+        );
+        let boards = Account::parse_boards(html.as_ref(), &url(SUBSCRIBED_BOARDS_PATH)).unwrap();
+
+        assert_eq!(
+            boards.iter().map(|b| b.id.as_ref()).collect::<Vec<&str>>(),
+            vec!["60076", "36730"]
+        );
+    }
+
+    #[test]
+    fn empty_only_when_the_page_says_so() {
+        let saved = url(SAVED_POSTS_PATH);
+
+        let html = page(r#"<div class="BH-lbox"><h1>收藏文章</h1><p>目前沒有收藏的文章</p></div>"#);
+        assert!(Account::parse_saved_posts(html.as_ref(), &saved)
+            .unwrap()
+            .is_empty());
+
+        let res = Account::parse_saved_posts("<html><body></body></html>", &saved);
+        assert!(matches!(res, Err(Error::Layout(_))));
+
+        let html = page(r#"<form><input type="password" name="pwd"></form>"#);
+        let res = Account::parse_saved_posts(html.as_ref(), &saved);
+        assert!(matches!(res, Err(Error::Auth(_))));
+    }
+
+    #[test]
+    fn notifications_with_and_without_links() {
+        let html = page(
+            r#"<div class="notify-list"><ul>
+<li><a href="C.php?bsn=60076&amp;snA=1">abc 回覆了你的文章</a><span class="time">2024-05-01 10:00</span></li>
+<li>系統公告 維護中 <span class="time">2024-05-02 11:00</span></li></ul></div>"#,
+        );
+        let notifications =
+            Account::parse_notifications(html.as_ref(), &url(NOTIFICATIONS_PATH)).unwrap();
+
+        assert_eq!(notifications.len(), 2);
+        assert_eq!(notifications[0].text, "abc 回覆了你的文章");
+        assert!(notifications[0].post.is_some());
+        assert_eq!(notifications[1].text, "系統公告 維護中");
+        assert_eq!(notifications[1].url, None);
+        assert!(notifications[1].time.is_parsed());
+    }
+}
@@ -0,0 +1,221 @@
+//! Blocking wrappers over the async api, enabled by the `blocking` feature.
+//!
+//! Every call runs on a runtime shared by the whole process, so these
+//! functions must not be called from async code.
+
+use std::{
+    future::Future,
+    ops::{Deref, DerefMut},
+    sync::OnceLock,
+};
+
+use tokio::runtime::{Builder, Runtime};
+
+use super::{
+    account::{Account, Notification},
+    board::{BoardInfo, BoardPost},
+    post::{PostComment, PostContent, PostPage, Vote, VoteResult},
+    search::{BoardSearch, ForumHot, SearchResult},
+    user::UserProfile,
+    BahamutClient, CachedPage, Error, WebSite,
+};
+
+static RUNTIME: OnceLock<Runtime> = OnceLock::new();
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    RUNTIME
+        .get_or_init(|| {
+            Builder::new_multi_thread()
+                .worker_threads(1)
+                .enable_all()
+                .build()
+                .expect("build blocking runtime fail")
+        })
+        .block_on(future)
+}
+
+/// Blocking version of a [`CachedPage`] such as `BoardPage` or `PostPage`.
+///
+/// ```no_run
+/// use bahamut::api::{blocking::Blocking, board::BoardPage, BahamutClient};
+///
+/// let client = BahamutClient::new();
+/// let mut page = Blocking::new(BoardPage::new(&client, "60076"));
+/// page.init()?;
+/// let board = page.get_current()?;
+/// # Ok::<(), bahamut::Error>(())
+/// ```
+pub struct Blocking<P> {
+    inner: P,
+}
+
+impl<P> Blocking<P> {
+    pub fn new(inner: P) -> Blocking<P> {
+        Blocking { inner }
+    }
+
+    pub fn into_inner(self) -> P {
+        self.inner
+    }
+
+    pub fn init<T>(&mut self) -> Result<(), Error>
+    where
+        P: CachedPage<T>,
+        T: TryFrom<WebSite, Error = Error> + Clone + Send + Sync,
+    {
+        block_on(self.inner.init())
+    }
+
+    pub fn get<T>(&self, page: u16, ignore_cache: bool) -> Result<T, Error>
+    where
+        P: CachedPage<T>,
+        T: TryFrom<WebSite, Error = Error> + Clone + Send + Sync,
+    {
+        block_on(self.inner.get(page, ignore_cache))
+    }
+
+    pub fn get_and_cache<T>(&mut self, page: u16, ignore_cache: bool) -> Result<T, Error>
+    where
+        P: CachedPage<T>,
+        T: TryFrom<WebSite, Error = Error> + Clone + Send + Sync,
+    {
+        block_on(self.inner.get_and_cache(page, ignore_cache))
+    }
+
+    pub fn get_current<T>(&self) -> Result<T, Error>
+    where
+        P: CachedPage<T>,
+        T: TryFrom<WebSite, Error = Error> + Clone + Send + Sync,
+    {
+        block_on(self.inner.get_current())
+    }
+
+    pub fn get_current_and_cache<T>(&mut self) -> Result<T, Error>
+    where
+        P: CachedPage<T>,
+        T: TryFrom<WebSite, Error = Error> + Clone + Send + Sync,
+    {
+        block_on(self.inner.get_current_and_cache())
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn next<T>(&mut self) -> Result<T, Error>
+    where
+        P: CachedPage<T>,
+        T: TryFrom<WebSite, Error = Error> + Clone + Send + Sync,
+    {
+        block_on(self.inner.next())
+    }
+
+    pub fn previous<T>(&mut self) -> Result<T, Error>
+    where
+        P: CachedPage<T>,
+        T: TryFrom<WebSite, Error = Error> + Clone + Send + Sync,
+    {
+        block_on(self.inner.previous())
+    }
+}
+
+impl<P> Deref for Blocking<P> {
+    type Target = P;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<P> DerefMut for Blocking<P> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
+
+pub fn get_search_result(client: &BahamutClient, query: &str) -> Result<Vec<SearchResult>, Error> {
+    block_on(BoardSearch::get_search_result(client, query))
+}
+
+pub fn get_hot(client: &BahamutClient) -> Result<ForumHot, Error> {
+    block_on(ForumHot::get_hot(client))
+}
+
+pub fn get_comment(
+    client: &BahamutClient,
+    id: String,
+    c_id: String,
+) -> Result<Vec<PostComment>, Error> {
+    block_on(PostComment::get_comment(client, id, c_id))
+}
+
+pub fn get_all_comment(
+    client: &BahamutClient,
+    id: String,
+    c_id: String,
+) -> Result<Vec<PostComment>, Error> {
+    block_on(PostComment::get_all_comment(client, id, c_id))
+}
+
+pub fn get_board_info(client: &BahamutClient, id: &str) -> Result<BoardInfo, Error> {
+    block_on(BoardInfo::get_info(client, id))
+}
+
+pub fn get_user_profile(client: &BahamutClient, id: &str) -> Result<UserProfile, Error> {
+    block_on(UserProfile::get_profile(client, id))
+}
+
+pub fn get_subscribed_boards(client: &BahamutClient) -> Result<Vec<SearchResult>, Error> {
+    block_on(Account::get_subscribed_boards(client))
+}
+
+pub fn get_followed_posts(client: &BahamutClient) -> Result<Vec<BoardPost>, Error> {
+    block_on(Account::get_followed_posts(client))
+}
+
+pub fn get_saved_posts(client: &BahamutClient) -> Result<Vec<BoardPost>, Error> {
+    block_on(Account::get_saved_posts(client))
+}
+
+pub fn get_notifications(client: &BahamutClient) -> Result<Vec<Notification>, Error> {
+    block_on(Account::get_notifications(client))
+}
+
+pub fn send_comment(
+    client: &BahamutClient,
+    id: &str,
+    c_id: &str,
+    content: &str,
+) -> Result<(), Error> {
+    block_on(PostComment::send_comment(client, id, c_id, content))
+}
+
+pub fn edit_comment(
+    client: &BahamutClient,
+    comment: &PostComment,
+    content: &str,
+) -> Result<(), Error> {
+    block_on(comment.edit(client, content))
+}
+
+pub fn delete_comment(client: &BahamutClient, comment: &PostComment) -> Result<(), Error> {
+    block_on(comment.delete(client))
+}
+
+pub fn reply(page: &PostPage, content: &str) -> Result<(), Error> {
+    block_on(page.reply(content))
+}
+
+pub fn vote_floor(
+    client: &BahamutClient,
+    id: &str,
+    c_id: &str,
+    vote: Vote,
+) -> Result<VoteResult, Error> {
+    block_on(PostContent::vote(client, id, c_id, vote))
+}
+
+pub fn vote_comment(
+    client: &BahamutClient,
+    comment: &PostComment,
+    vote: Vote,
+) -> Result<VoteResult, Error> {
+    block_on(comment.vote(client, vote))
+}
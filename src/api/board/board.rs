@@ -1,12 +1,13 @@
 use std::collections::HashMap;
 
-use scraper::{Html, Selector};
+use scraper::Html;
 use url::Url;
 
-use crate::api::{UrlWithId, WebSite, DN};
+use crate::api::{is_missing_page, parse::Field, Error, ParseError, UrlWithId, WebSite};
 
 use super::{
     category::{BoardCategory, BoardCategoryId},
+    info::BoardInfo,
     post::BoardPost,
 };
 
@@ -15,7 +16,14 @@ pub struct Board {
     pub name: String,
     pub category: HashMap<String, BoardCategory>,
 
-    document: Html,
+    /// Fields which can't be read and were left to their default
+    pub parse_errors: Vec<ParseError>,
+
+    url: Url,
+    /// `Html` isn't `Send`, keep the source and parse rows on demand
+    html: String,
+    /// Author filtered on our side, see [`BoardPostQuery`](super::BoardPostQuery)
+    author: Option<String>,
 }
 
 impl Clone for Board {
@@ -29,78 +37,153 @@ impl Clone for Board {
             id: self.id.to_owned(),
             name: self.name.to_owned(),
             category: new_map,
-            document: self.document.clone(),
+            parse_errors: self.parse_errors.clone(),
+            url: self.url.clone(),
+            html: self.html.clone(),
+            author: self.author.clone(),
         }
     }
 }
 
 impl UrlWithId<(&str, u16)> for Board {
-    fn url(p: (&str, u16)) -> Url {
-        let url = format!("{}{}?bsn={}&page={}", DN, "B.php", p.0, p.1);
-        Url::parse(url.as_ref()).expect("invalid url")
+    fn url(base: &Url, p: (&str, u16)) -> Url {
+        let url = format!("{}?bsn={}&page={}", "B.php", p.0, p.1);
+        base.join(url.as_ref()).expect("invalid url")
     }
 }
 
 impl Board {
+    /// Parse a saved `B.php` page, `url` is the page url used to resolve
+    /// post links and must contain the `bsn` query.
+    pub fn from_html(html: &str, url: &Url) -> Result<Board, Error> {
+        Board::try_from(WebSite::from_html(html, url))
+    }
+
+    /// Posts of the page, ads and rows which can't be parsed are skipped.
     pub fn post(&self) -> Vec<BoardPost> {
-        let selector = Selector::parse(".b-list__row").expect("parse selector error");
-        self.document
-            .select(&selector)
-            .filter_map(|root| BoardPost::try_from(root).ok())
+        self.try_post()
+            .into_iter()
+            .filter_map(|post| post.ok())
+            .filter(|post| !post.flags.ad)
+            .filter(|post| {
+                self.author
+                    .as_ref()
+                    .is_none_or(|a| post.author.eq_ignore_ascii_case(a))
+            })
             .collect::<Vec<BoardPost>>()
     }
 
-    fn try_name_from_html(document: &Html) -> Option<String> {
-        let selector = Selector::parse("head title").expect("parse selector error");
-        let title = document
-            .select(&selector)
-            .next()
-            .expect("get name fail")
-            .text()
-            .collect::<String>();
+    /// Description, moderators and stats shown on the page.
+    pub fn info(&self) -> Result<BoardInfo, Error> {
+        BoardInfo::from_html(self.html.as_ref(), &self.url)
+    }
 
-        let name = title.split(' ').next().map(String::from);
-        name
+    /// Categories sorted by their `subbsn`.
+    pub fn categories(&self) -> Vec<BoardCategory> {
+        let mut list = self
+            .category
+            .values()
+            .cloned()
+            .collect::<Vec<BoardCategory>>();
+        list.sort_by_key(|c| (c.id.sub_id.parse::<u32>().unwrap_or(u32::MAX), c.id()));
+        list
     }
 
-    fn try_id_from_url(url: &Url) -> Option<String> {
-        let query = url
-            .query_pairs()
-            .find(|(k, _)| k == "bsn")
-            .map(|(_, v)| v)
-            .unwrap();
+    /// Only keep posts of `author` in [`Board::post`].
+    pub fn filter_author(&mut self, author: Option<&str>) -> &Self {
+        self.author = author.map(String::from);
+        self
+    }
 
-        Some(query.to_string())
+    /// Every row of the list, including the ones which can't be parsed.
+    pub fn try_post(&self) -> Vec<Result<BoardPost, Error>> {
+        let field = Field::new("Board::post", ".b-list__row");
+        let document = Html::parse_document(self.html.as_ref());
+        match field.all(&document.root_element()) {
+            Ok(rows) => rows
+                .into_iter()
+                .map(|root| BoardPost::try_from((root, &self.url)))
+                .collect::<Vec<Result<BoardPost, Error>>>(),
+            Err(e) => vec![Err(e.into())],
+        }
     }
 
-    fn try_category_map_from_html(document: &Html) -> Option<HashMap<String, BoardCategory>> {
-        let mut map: HashMap<String, BoardCategory> = HashMap::new();
+    pub(super) fn try_name_from_html(document: &Html) -> Result<String, ParseError> {
+        let field = Field::new("Board::name", "head title");
+        let title = field.text(&document.root_element())?;
+
+        let name = title
+            .split(' ')
+            .next()
+            .map_or(String::from(""), String::from);
+        Ok(name)
+    }
 
-        let selector = Selector::parse(".b-tags__item a").expect("parse selector error");
-        document.select(&selector).for_each(|elm| {
-            let href = elm.value().attr("href").unwrap();
-            let url = Url::parse(href).unwrap();
-            let id = BoardCategoryId::try_from(url).unwrap();
-            let name = elm.text().collect::<String>();
+    fn try_id_from_url(url: &Url) -> Result<String, ParseError> {
+        Field::new("Board::id", "bsn").query(url)
+    }
+
+    fn try_category_map_from_html(
+        document: &Html,
+        base: &Url,
+        errors: &mut Vec<ParseError>,
+    ) -> HashMap<String, BoardCategory> {
+        let mut map: HashMap<String, BoardCategory> = HashMap::new();
 
-            map.insert(id.sub_id.to_owned(), BoardCategory { id, name });
+        let field = Field::new("Board::category", ".b-tags__item a");
+        let items = match field.all(&document.root_element()) {
+            Ok(items) => items,
+            Err(e) => {
+                errors.push(e);
+                return map;
+            }
+        };
+
+        items.into_iter().for_each(|elm| {
+            let url = field
+                .attr(&elm, "href")
+                .and_then(|href| field.url(base, href));
+
+            match url {
+                Ok(url) => {
+                    let id = BoardCategoryId::from(url);
+                    let name = elm.text().collect::<String>();
+                    map.insert(id.sub_id.to_owned(), BoardCategory { id, name });
+                }
+                Err(e) => errors.push(e),
+            }
         });
 
-        Some(map)
+        map
     }
 }
 
 impl TryFrom<WebSite> for Board {
-    type Error = &'static str;
+    type Error = Error;
 
-    fn try_from(web: WebSite) -> Result<Self, &'static str> {
+    fn try_from(web: WebSite) -> Result<Self, Error> {
         let WebSite { document, url } = web;
 
+        let rows = Field::new("Board::post", ".b-list__row").first(&document.root_element());
+        if rows.is_err() && is_missing_page(&document) {
+            return Err(Error::NotFound);
+        }
+
+        let mut parse_errors: Vec<ParseError> = vec![];
+        let name = Board::try_name_from_html(&document).unwrap_or_else(|e| {
+            parse_errors.push(e);
+            String::from("")
+        });
+        let category = Board::try_category_map_from_html(&document, &url, &mut parse_errors);
+
         Ok(Board {
-            name: Board::try_name_from_html(&document).map_or(String::from(""), |v| v),
-            id: Board::try_id_from_url(&url).ok_or("id invalid")?,
-            category: Board::try_category_map_from_html(&document).ok_or("category invalid")?,
-            document,
+            name,
+            id: Board::try_id_from_url(&url)?,
+            category,
+            parse_errors,
+            url,
+            html: document.html(),
+            author: None,
         })
     }
 }
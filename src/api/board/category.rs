@@ -2,7 +2,7 @@ use serde::Serialize;
 
 use url::Url;
 
-use crate::api::{UrlWithId, DN};
+use crate::api::UrlWithId;
 
 #[derive(Clone, Serialize)]
 pub struct BoardCategoryId {
@@ -10,10 +10,8 @@ pub struct BoardCategoryId {
     pub sub_id: String,
 }
 
-impl TryFrom<Url> for BoardCategoryId {
-    type Error = &'static str;
-
-    fn try_from(url: Url) -> Result<Self, Self::Error> {
+impl From<Url> for BoardCategoryId {
+    fn from(url: Url) -> Self {
         let empty = "";
         let mut id: String = empty.to_string();
         let mut sub_id: String = empty.to_string();
@@ -21,12 +19,12 @@ impl TryFrom<Url> for BoardCategoryId {
         url.query_pairs().for_each(|(k, v)| {
             if k == "bsn" {
                 id = v.to_string();
-            } else {
+            } else if k == "subbsn" {
                 sub_id = v.to_string();
             }
         });
 
-        Ok(BoardCategoryId { id, sub_id })
+        BoardCategoryId { id, sub_id }
     }
 }
 
@@ -37,9 +35,9 @@ pub struct BoardCategory {
 }
 
 impl UrlWithId<(&str, &str)> for BoardCategory {
-    fn url(p: (&str, &str)) -> Url {
-        let url = format!("{}{}?bsn={}&subbsn={}", DN, "B.php", p.0, p.1);
-        Url::parse(url.as_ref()).expect("invalid url")
+    fn url(base: &Url, p: (&str, &str)) -> Url {
+        let url = format!("{}?bsn={}&subbsn={}", "B.php", p.0, p.1);
+        base.join(url.as_ref()).expect("invalid url")
     }
 }
 
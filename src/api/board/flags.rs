@@ -0,0 +1,123 @@
+use scraper::ElementRef;
+use serde::Serialize;
+use url::Url;
+
+/// How a row of the board list is marked.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct BoardPostFlags {
+    /// 置頂
+    pub pinned: bool,
+    pub locked: bool,
+    /// 精華
+    pub digest: bool,
+    pub deleted: bool,
+    /// Ads mixed into the list, they don't link to a post
+    pub ad: bool,
+}
+
+impl BoardPostFlags {
+    /// Read the classes and marks of a `.b-list__row`.
+    pub fn from_html(elm: &ElementRef) -> BoardPostFlags {
+        let classes = elm
+            .descendants()
+            .filter_map(ElementRef::wrap)
+            .flat_map(|e| e.value().classes().collect::<Vec<&str>>())
+            .collect::<Vec<&str>>();
+        // `keys` are whole class names or `--` modifiers, as in `b-list__row--sticky`
+        let has_class = |keys: &[&str]| {
+            classes.iter().any(|c| {
+                let modifier = c.rsplit_once("--").map(|(_, modifier)| modifier);
+                keys.iter().any(|k| c == k || modifier == Some(k))
+            })
+        };
+
+        // the title and brief may contain the same words
+        let marks = elm
+            .descendants()
+            .filter_map(ElementRef::wrap)
+            .filter(|e| e.value().classes().any(|c| c.contains("mark")))
+            .flat_map(|e| e.text())
+            .collect::<String>();
+        let has_mark = |keys: &[&str]| keys.iter().any(|k| marks.contains(k));
+
+        BoardPostFlags {
+            pinned: has_class(&["sticky", "top"]) || has_mark(&["置頂"]),
+            locked: has_class(&["lock", "is-lock", "icon-lock"]) || has_mark(&["鎖定"]),
+            digest: has_class(&["digest", "marrow", "is-digest"]) || has_mark(&["精華"]),
+            deleted: has_class(&["delete", "is-del"]) || has_mark(&["本文已刪除"]),
+            ad: has_class(&["ad", "sponsor", "adsbygoogle"]),
+        }
+    }
+}
+
+/// Order of the board list.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum BoardSort {
+    /// Posts with the latest reply first, the site default
+    #[default]
+    LatestReply,
+    /// Newest posts first
+    NewestPost,
+}
+
+impl BoardSort {
+    /// Add the ordering query of `B.php`, the default one doesn't have any.
+    pub(crate) fn append_to(&self, url: &mut Url) {
+        if let BoardSort::NewestPost = self {
+            url.query_pairs_mut().append_pair("last", "1");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use scraper::{Html, Selector};
+
+    fn flags(row: &str) -> BoardPostFlags {
+        let html = Html::parse_fragment(row);
+        let selector = Selector::parse(".b-list__row").unwrap();
+        let elm = html.select(&selector).next().unwrap();
+        BoardPostFlags::from_html(&elm)
+    }
+
+    #[test]
+    fn flags_from_classes() {
+        let row = r#"<div class="b-list__row b-list__row--sticky">
+            <i class="icon-lock"></i><span class="b-list__summary--marrow"></span></div>"#;
+        assert_eq!(
+            flags(row),
+            BoardPostFlags {
+                pinned: true,
+                locked: true,
+                digest: true,
+                ..Default::default()
+            }
+        );
+
+        let row = r#"<div class="b-list__row b-list__row--sponsor"></div>"#;
+        assert!(flags(row).ad);
+        assert!(flags(r#"<div class="b-list__row b-list__row--delete"></div>"#).deleted);
+    }
+
+    #[test]
+    fn flags_match_whole_modifiers() {
+        let row = r#"<div class="b-list__row b-list__row--added">
+            <span class="tag--adult b-list__top-reply"></span><i class="icon-locked"></i></div>"#;
+        assert_eq!(flags(row), BoardPostFlags::default());
+    }
+
+    #[test]
+    fn flags_from_marks_only() {
+        let row = r#"<div class="b-list__row">
+            <span class="b-list__summary__mark">置頂</span>
+            <span class="b-list__main__title">精華 鎖定 本文已刪除</span></div>"#;
+        assert_eq!(
+            flags(row),
+            BoardPostFlags {
+                pinned: true,
+                ..Default::default()
+            }
+        );
+    }
+}
@@ -0,0 +1,289 @@
+use scraper::{ElementRef, Html};
+use serde::Serialize;
+use url::Url;
+
+use crate::api::{
+    get_document, is_missing_page, parse::Field, BahamutClient, Error, ParseError, ParseErrorKind,
+    UrlWithId, WebSite,
+};
+
+use super::board::Board;
+
+#[derive(Clone, Default, Serialize)]
+pub struct BoardModerator {
+    pub id: String,
+    pub name: String,
+}
+
+/// Metadata of a board shown around its post list.
+#[derive(Clone, Default, Serialize)]
+pub struct BoardInfo {
+    pub id: String,
+    pub name: String,
+    pub desc: String,
+    pub moderators: Vec<BoardModerator>,
+    /// Link to the rules (版規) of the board
+    pub rules: Option<String>,
+    /// Popularity (人氣) of the board
+    pub popularity: u32,
+    /// Members subscribing (訂閱) the board
+    pub subscribers: u32,
+    pub icon: Option<String>,
+
+    /// Fields which can't be read and were left to their default
+    pub parse_errors: Vec<ParseError>,
+}
+
+impl BoardInfo {
+    /// Fetch the info from the first page of the board.
+    pub async fn get_info(client: &BahamutClient, id: &str) -> Result<BoardInfo, Error> {
+        let url = Board::url(client.base_url(), (id, 1));
+        let document = get_document(client, &url).await?;
+        BoardInfo::try_from(WebSite { url, document })
+    }
+
+    /// Parse a saved `B.php` page, `url` must contain the `bsn` query.
+    pub fn from_html(html: &str, url: &Url) -> Result<BoardInfo, Error> {
+        BoardInfo::try_from(WebSite::from_html(html, url))
+    }
+
+    fn try_meta_from_html(
+        document: &Html,
+        field: Field,
+        base: Option<&Url>,
+    ) -> Result<String, ParseError> {
+        let content = field.attr(&field.first(&document.root_element())?, "content")?;
+        match base {
+            Some(base) => field.url(base, content).map(String::from),
+            None => Ok(content.trim().to_string()),
+        }
+    }
+
+    /// Links to the home of users listed next to a `版主` label.
+    fn try_moderators_from_html(document: &Html) -> Result<Vec<BoardModerator>, ParseError> {
+        let field = Field::new("BoardInfo::moderators", "a[href*=\"home.gamer.com.tw\"]");
+        let rows = BoardInfo::rows(document)?;
+
+        let mut moderators: Vec<BoardModerator> = vec![];
+        field
+            .all(&document.root_element())?
+            .into_iter()
+            .filter(|a| {
+                !a.ancestors()
+                    .any(|p| rows.iter().any(|row| row.id() == p.id()))
+            })
+            .filter(|a| {
+                // the whole page has the label somewhere
+                a.ancestors()
+                    .take(3)
+                    .filter_map(ElementRef::wrap)
+                    .take_while(|elm| !matches!(elm.value().name(), "body" | "html"))
+                    .any(|elm| elm.text().any(|text| text.contains("版主")))
+            })
+            .for_each(|a| {
+                let Some(id) = a.value().attr("href").and_then(user_id_from_href) else {
+                    return;
+                };
+
+                if !moderators.iter().any(|m| m.id.eq_ignore_ascii_case(&id)) {
+                    let name = a.text().collect::<String>().trim().to_string();
+                    moderators.push(BoardModerator { id, name });
+                }
+            });
+
+        if moderators.is_empty() {
+            Err(field.error(ParseErrorKind::Missing))
+        } else {
+            Ok(moderators)
+        }
+    }
+
+    fn try_rules_from_html(document: &Html, base: &Url) -> Result<String, ParseError> {
+        let field = Field::new("BoardInfo::rules", "a[href]");
+        let link = field
+            .all(&document.root_element())?
+            .into_iter()
+            .find(|a| a.text().collect::<String>().contains("版規"))
+            .ok_or_else(|| field.error(ParseErrorKind::Missing))?;
+
+        field
+            .url(base, field.attr(&link, "href")?)
+            .map(|url| url.to_string())
+    }
+
+    /// Rows of the post list, user links and titles in them aren't board info.
+    fn rows(document: &Html) -> Result<Vec<ElementRef<'_>>, ParseError> {
+        Field::new("BoardInfo::rows", ".b-list__row").all(&document.root_element())
+    }
+
+    /// Number written after `label` outside the post list, as in `人氣：12,345`.
+    /// The label also names buttons, such as the `訂閱` one, which are skipped.
+    fn try_stat_from_html(
+        document: &Html,
+        field: Field,
+        label: &'static str,
+    ) -> Result<u32, ParseError> {
+        let rows = BoardInfo::rows(document)?;
+        let text = field
+            .first(&document.root_element())?
+            .descendants()
+            .filter(|node| {
+                !node
+                    .ancestors()
+                    .any(|p| rows.iter().any(|row| row.id() == p.id()))
+            })
+            .filter_map(|node| node.value().as_text().map(|t| t.to_string()))
+            .collect::<String>();
+        let number = text
+            .match_indices(label)
+            .map(|(i, _)| {
+                text[i + label.len()..]
+                    .trim_start_matches(|c: char| c.is_whitespace() || c == ':' || c == '：')
+                    .chars()
+                    .take_while(|c| c.is_ascii_digit() || *c == ',')
+                    .filter(|c| *c != ',')
+                    .collect::<String>()
+            })
+            .find(|number| !number.is_empty())
+            .ok_or_else(|| field.error(ParseErrorKind::Label(label)))?;
+
+        field.number(&number)
+    }
+}
+
+/// `home.gamer.com.tw/homeindex.php?owner=id` or `home.gamer.com.tw/id`
+fn user_id_from_href(href: &str) -> Option<String> {
+    let url = Url::parse(href)
+        .or_else(|_| Url::parse(format!("https:{}", href).as_ref()))
+        .ok()?;
+
+    let owner = url
+        .query_pairs()
+        .find(|(k, _)| k == "owner")
+        .map(|(_, v)| v.to_string());
+
+    owner
+        .or_else(|| {
+            url.path_segments()?
+                .find(|s| !s.is_empty() && !s.contains('.'))
+                .map(String::from)
+        })
+        .filter(|id| !id.is_empty())
+}
+
+impl TryFrom<WebSite> for BoardInfo {
+    type Error = Error;
+
+    fn try_from(web: WebSite) -> Result<Self, Error> {
+        let WebSite { document, url } = web;
+
+        let rows = Field::new("Board::post", ".b-list__row").first(&document.root_element());
+        if rows.is_err() && is_missing_page(&document) {
+            return Err(Error::NotFound);
+        }
+
+        let mut info = BoardInfo {
+            id: Field::new("BoardInfo::id", "bsn").query(&url)?,
+            ..BoardInfo::default()
+        };
+
+        match Board::try_name_from_html(&document) {
+            Ok(name) => info.name = name,
+            Err(e) => info.parse_errors.push(e),
+        };
+
+        let field = Field::new("BoardInfo::desc", "meta[name=\"description\"]");
+        match BoardInfo::try_meta_from_html(&document, field, None) {
+            Ok(desc) => info.desc = desc,
+            Err(e) => info.parse_errors.push(e),
+        };
+
+        let field = Field::new("BoardInfo::icon", "meta[property=\"og:image\"]");
+        match BoardInfo::try_meta_from_html(&document, field, Some(&url)) {
+            Ok(icon) => info.icon = Some(icon),
+            Err(e) => info.parse_errors.push(e),
+        };
+
+        match BoardInfo::try_moderators_from_html(&document) {
+            Ok(moderators) => info.moderators = moderators,
+            Err(e) => info.parse_errors.push(e),
+        };
+
+        match BoardInfo::try_rules_from_html(&document, &url) {
+            Ok(rules) => info.rules = Some(rules),
+            Err(e) => info.parse_errors.push(e),
+        };
+
+        let field = Field::new("BoardInfo::popularity", "body");
+        match BoardInfo::try_stat_from_html(&document, field, "人氣") {
+            Ok(n) => info.popularity = n,
+            Err(e) => info.parse_errors.push(e),
+        };
+
+        let field = Field::new("BoardInfo::subscribers", "body");
+        match BoardInfo::try_stat_from_html(&document, field, "訂閱") {
+            Ok(n) => info.subscribers = n,
+            Err(e) => info.parse_errors.push(e),
+        };
+
+        Ok(info)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `B.php` trimmed to the header, the board info box and one row.
+    const BOARD_PAGE: &str = r#"<html><head>
+<title>場外休憩區 看板列表 - 巴哈姆特</title>
+<meta name="description" content="什麼都能聊的地方">
+<meta property="og:image" content="https://p2.bahamut.com.tw/B/2KU/76/0000060076.PNG">
+</head><body>
+<div class="TOP-my"><ul><li><a href="https://home.gamer.com.tw/homeindex.php?owner=me">我的小屋</a></li></ul></div>
+<div class="b-list-wrap"><table class="b-list"><tbody>
+<tr class="b-list__row"><td class="b-list__main"><a href="C.php?bsn=60076&amp;snA=1">版主 人氣 5</a></td>
+<td class="b-list__count"><a href="https://home.gamer.com.tw/homeindex.php?owner=poster">poster</a></td></tr>
+</tbody></table></div>
+<div class="BH-rbox BH-list"><h5>看板資訊</h5><ul>
+<li><span>版主：</span><a href="https://home.gamer.com.tw/homeindex.php?owner=mod1">Mod One</a>、<a href="//home.gamer.com.tw/mod2">Mod2</a></li>
+<li>人氣：12,345</li>
+<li><a class="btn-subscribe" href="javascript:;">訂閱</a> 本板訂閱：1,234</li>
+<li><a href="C.php?bsn=60076&amp;snA=2">場外版規</a></li>
+</ul></div>
+</body></html>"#;
+
+    #[test]
+    fn info_from_board_page() {
+        let url = Url::parse("https://forum.gamer.com.tw/B.php?bsn=60076").unwrap();
+        let info = BoardInfo::from_html(BOARD_PAGE, &url).unwrap();
+
+        assert_eq!(info.name, "場外休憩區");
+        assert_eq!(info.desc, "什麼都能聊的地方");
+        assert_eq!(
+            info.moderators
+                .iter()
+                .map(|m| (m.id.as_ref(), m.name.as_ref()))
+                .collect::<Vec<(&str, &str)>>(),
+            vec![("mod1", "Mod One"), ("mod2", "Mod2")]
+        );
+        assert_eq!(
+            info.rules.as_deref(),
+            Some("https://forum.gamer.com.tw/C.php?bsn=60076&snA=2")
+        );
+        assert_eq!(info.popularity, 12345);
+        assert_eq!(info.subscribers, 1234);
+        assert!(info.parse_errors.is_empty());
+    }
+
+    #[test]
+    fn stat_needs_a_number() {
+        let document = Html::parse_document("<body><a>訂閱</a> 訂閱 人數</body>");
+        let field = Field::new("BoardInfo::subscribers", "body");
+
+        assert_eq!(
+            BoardInfo::try_stat_from_html(&document, field, "訂閱").map_err(|e| e.kind),
+            Err(ParseErrorKind::Label("訂閱"))
+        );
+    }
+}
@@ -1,10 +1,16 @@
 #[allow(clippy::module_inception)]
 mod board;
 mod category;
+mod flags;
+mod info;
 mod page;
 mod post;
+mod query;
 
 pub use board::Board;
 pub use category::{BoardCategory, BoardCategoryId};
+pub use flags::{BoardPostFlags, BoardSort};
+pub use info::{BoardInfo, BoardModerator};
 pub use page::BoardPage;
 pub use post::BoardPost;
+pub use query::BoardPostQuery;
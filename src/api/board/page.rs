@@ -1,69 +1,106 @@
 use std::collections::HashMap;
 
-use scraper::{ElementRef, Html, Selector};
+use async_trait::async_trait;
+use scraper::{ElementRef, Html};
 use url::Url;
 
-use crate::api::{CachedPage, UrlWithId};
+use crate::api::{parse::Field, BahamutClient, CachedPage, Error, ParseError, UrlWithId};
 
-use super::board::Board;
+use super::{board::Board, category::BoardCategory, flags::BoardSort, query::BoardPostQuery};
 
 pub struct BoardPage {
     pub id: String,
     pub page: u16,
     pub max: u16,
-
-    cache: HashMap<u16, Option<Board>>,
-    first_page_cache: Option<Html>,
+    /// Page through the search results instead of the whole board
+    pub query: Option<BoardPostQuery>,
+    /// `subbsn` of the category to page through, see [`Board::category`]
+    pub category: Option<String>,
+    pub sort: BoardSort,
+
+    client: BahamutClient,
+    cache: HashMap<u16, Board>,
+    first_page_cache: Option<String>,
 }
 
 impl BoardPage {
-    pub fn new(id: &str) -> BoardPage {
+    pub fn new(client: &BahamutClient, id: &str) -> BoardPage {
         BoardPage {
             id: id.to_string(),
             page: 1,
             max: 0,
+            query: None,
+            category: None,
+            sort: BoardSort::default(),
+            client: client.clone(),
             cache: HashMap::new(),
             first_page_cache: None,
         }
     }
 
-    pub fn from_page(id: &str, page: u16) -> BoardPage {
+    pub fn from_page(client: &BahamutClient, id: &str, page: u16) -> BoardPage {
         BoardPage {
             id: id.to_string(),
             page,
             max: 0,
+            query: None,
+            category: None,
+            sort: BoardSort::default(),
+            client: client.clone(),
             cache: HashMap::new(),
             first_page_cache: None,
         }
     }
 
-    pub fn init(&mut self) {
-        if let Some(document) = self.get_page_html(1) {
-            let root = document.root_element();
-            let max = BoardPage::try_page_from_html(&root).map_or(0, |v| v);
-            self.max = max;
-            self.first_page_cache = Some(document);
-        }
+    /// Search results of `query` in the board, paged like the board itself.
+    pub fn search(client: &BahamutClient, id: &str, query: BoardPostQuery) -> BoardPage {
+        let mut page = BoardPage::new(client, id);
+        page.query = Some(query).filter(|q| !q.is_empty());
+        page
     }
 
-    fn try_page_from_html(document: &ElementRef) -> Option<u16> {
-        let selector = Selector::parse(".BH-pagebtnA a").unwrap();
-        let last = document.select(&selector).last().unwrap();
-        let page: u16 = last.text().collect::<String>().parse().unwrap();
-        Some(page)
+    /// Posts of a single category of the board.
+    pub fn from_category(client: &BahamutClient, category: &BoardCategory) -> BoardPage {
+        let mut page = BoardPage::new(client, category.board_id().as_ref());
+        page.category = Some(category.id()).filter(|id| !id.is_empty());
+        page
+    }
+
+    fn try_page_from_html(document: &ElementRef) -> Result<u16, ParseError> {
+        let field = Field::new("BoardPage::max", ".BH-pagebtnA a");
+
+        // boards with a single page don't have the page buttons
+        match field.all(document)?.last() {
+            Some(last) => field.number(&last.text().collect::<String>()),
+            None => Ok(1),
+        }
     }
 }
 
+#[async_trait]
 impl CachedPage<Board> for BoardPage {
-    fn cache(&self) -> &HashMap<u16, Option<Board>> {
+    fn client(&self) -> &BahamutClient {
+        &self.client
+    }
+
+    fn cache(&self) -> &HashMap<u16, Board> {
         &self.cache
     }
 
-    fn insert_cache(&mut self, page: &u16, obj: Option<Board>) {
+    fn insert_cache(&mut self, page: &u16, obj: Board) {
         self.cache.insert(*page, obj);
     }
 
-    fn cached_page_html(&self, page: u16) -> Option<Html> {
+    async fn init(&mut self) -> Result<(), Error> {
+        let html = self.get_page_html(1).await?;
+        let document = Html::parse_document(html.as_ref());
+        let max = BoardPage::try_page_from_html(&document.root_element()).unwrap_or(0);
+        self.max = max;
+        self.first_page_cache = Some(html);
+        Ok(())
+    }
+
+    fn cached_page_html(&self, page: u16) -> Option<String> {
         if page == 1 {
             self.first_page_cache.clone()
         } else {
@@ -71,8 +108,22 @@ impl CachedPage<Board> for BoardPage {
         }
     }
 
+    fn prepare(&self, mut board: Board) -> Board {
+        let author = self.query.as_ref().and_then(|q| q.client_author());
+        board.filter_author(author);
+        board
+    }
+
     fn url(&self, page: &u16) -> Url {
-        Board::url((self.id.as_str(), *page))
+        let mut url = Board::url(self.client.base_url(), (self.id.as_str(), *page));
+        if let Some(category) = &self.category {
+            url.query_pairs_mut().append_pair("subbsn", category);
+        }
+        if let Some(query) = &self.query {
+            query.append_to(&mut url);
+        }
+        self.sort.append_to(&mut url);
+        url
     }
 
     fn page(&self) -> u16 {
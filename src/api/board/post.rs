@@ -1,20 +1,30 @@
-use crate::api::{UrlWithId, DN};
+use crate::api::{parse::Field, Error, ParseError, Timestamp, UrlWithId};
 
-use super::category::{BoardCategory, BoardCategoryId};
+use super::{
+    category::{BoardCategory, BoardCategoryId},
+    flags::BoardPostFlags,
+};
 
-use scraper::{ElementRef, Selector};
+use scraper::ElementRef;
 use url::Url;
 
+#[derive(Clone)]
 pub struct BoardPost {
     pub id: String,
     pub title: String,
-    pub date: String,
+    /// Id of the author
+    pub author: String,
+    pub date: Timestamp,
     pub desc: String,
     pub category: BoardCategory,
     pub gp: u16,
     pub reply: u16,
     pub floor: u16,
     pub url: String,
+    pub flags: BoardPostFlags,
+
+    /// Fields which can't be read and were left to their default
+    pub parse_errors: Vec<ParseError>,
 }
 
 impl Default for BoardPost {
@@ -23,12 +33,15 @@ impl Default for BoardPost {
         BoardPost {
             id: String::from("0"),
             title: empty.to_string(),
-            date: empty.to_string(),
+            author: empty.to_string(),
+            date: Timestamp::default(),
             desc: empty.to_string(),
             url: empty.to_string(),
             gp: 0,
             reply: 0,
             floor: 0,
+            flags: BoardPostFlags::default(),
+            parse_errors: vec![],
             category: BoardCategory {
                 name: empty.to_string(),
                 id: BoardCategoryId {
@@ -41,20 +54,23 @@ impl Default for BoardPost {
 }
 
 impl UrlWithId<(&str, &str)> for BoardPost {
-    fn url(p: (&str, &str)) -> Url {
-        let url = format!("{}{}?bsn={}&snA={}", DN, "B.php", p.0, p.1);
-        Url::parse(url.as_ref()).expect("invalid url")
+    fn url(base: &Url, p: (&str, &str)) -> Url {
+        let url = format!("{}?bsn={}&snA={}", "B.php", p.0, p.1);
+        base.join(url.as_ref()).expect("invalid url")
     }
 }
 
 impl UrlWithId<(&str, &str, u16)> for BoardPost {
-    fn url(p: (&str, &str, u16)) -> Url {
-        let url = format!("{}{}?bsn={}&snA={}&tnum={}", DN, "B.php", p.0, p.1, p.2);
-        Url::parse(url.as_ref()).expect("invalid url")
+    fn url(base: &Url, p: (&str, &str, u16)) -> Url {
+        let url = format!("{}?bsn={}&snA={}&tnum={}", "B.php", p.0, p.1, p.2);
+        base.join(url.as_ref()).expect("invalid url")
     }
 }
 
 impl BoardPost {
+    /// Gp shown as `爆` in the board list
+    pub const GP_BURST: u16 = 1000;
+
     pub fn id(&mut self, id: String) -> &Self {
         self.id = id;
         self
@@ -70,7 +86,12 @@ impl BoardPost {
         self
     }
 
-    pub fn date(&mut self, date: String) -> &Self {
+    pub fn author(&mut self, author: String) -> &Self {
+        self.author = author;
+        self
+    }
+
+    pub fn date(&mut self, date: Timestamp) -> &Self {
         self.date = date;
         self
     }
@@ -94,85 +115,109 @@ impl BoardPost {
         self.floor = floor;
         self
     }
+
+    pub fn flags(&mut self, flags: BoardPostFlags) -> &Self {
+        self.flags = flags;
+        self
+    }
+
+    fn parse_error(&mut self, error: ParseError) -> &Self {
+        self.parse_errors.push(error);
+        self
+    }
+
+    /// The list shows `爆` instead of the number for popular posts.
+    fn try_gp_from_text(field: &Field, text: &str) -> Result<u16, ParseError> {
+        match text {
+            "" => Ok(0),
+            "爆" => Ok(BoardPost::GP_BURST),
+            _ => field.number::<u16>(text),
+        }
+    }
+
+    fn try_category_from_html(elm: &ElementRef, base: &Url) -> Result<BoardCategory, ParseError> {
+        let field = Field::new("BoardPost::category", ".b-list__summary__sort a");
+        let dom = field.first(elm)?;
+        let url = field.url(base, field.attr(&dom, "href")?)?;
+
+        Ok(BoardCategory {
+            name: dom.text().collect::<String>(),
+            id: BoardCategoryId::from(url),
+        })
+    }
 }
 
-impl TryFrom<ElementRef<'_>> for BoardPost {
-    type Error = &'static str;
+/// Parse a `.b-list__row`, links are resolved against the board page url.
+///
+/// Ads only have their [`BoardPost::flags`] and title. For other rows only a
+/// missing title or link fails the row, other fields fall back to their
+/// default and are reported in [`BoardPost::parse_errors`].
+impl TryFrom<(ElementRef<'_>, &Url)> for BoardPost {
+    type Error = Error;
 
-    fn try_from(elm: ElementRef) -> Result<Self, &'static str> {
+    fn try_from((elm, base): (ElementRef, &Url)) -> Result<Self, Error> {
         let mut post = BoardPost::default();
+        post.flags(BoardPostFlags::from_html(&elm));
 
-        // id
-        let selector = Selector::parse(".b-list__main a").expect("parse selector error");
-        if let Some(dom) = elm.select(&selector).next() {
-            let url = dom.value().attr("href").unwrap();
-            let url = format!("{}/{}", DN, url);
-            post.url = url.to_owned();
-
-            Url::parse(url.as_str())
-                .unwrap()
-                .query_pairs()
-                .into_iter()
-                .for_each(|(k, v)| {
-                    if k == "snA" {
-                        post.id(v.to_string());
-                    }
-
-                    if k == "tnum" {
-                        post.floor(v.parse::<u16>().map_or(0, |v| v));
-                    }
-                });
+        if post.flags.ad {
+            post.title(elm.text().collect::<String>().trim().to_string());
+            return Ok(post);
         }
 
-        // title
-        let selector = Selector::parse(".b-list__main__title").expect("parse selector error");
-        if let Some(dom) = elm.select(&selector).next() {
-            post.title(dom.text().collect::<String>().trim().into());
-        } else {
-            return Err("ad post");
+        let title = Field::new("BoardPost::title", ".b-list__main__title").text(&elm)?;
+        post.title(title);
+
+        // id
+        let field = Field::new("BoardPost::url", ".b-list__main a");
+        let href = field.attr(&field.first(&elm)?, "href")?;
+        let url = field.url(base, href)?;
+        post.url = url.to_string();
+        post.id(Field::new("BoardPost::id", "snA").query(&url)?);
+
+        if let Ok(floor) = Field::new("BoardPost::floor", "tnum").query(&url) {
+            post.floor(floor.parse::<u16>().map_or(0, |v| v));
         }
 
         // description
-        let selector = Selector::parse(".b-list__brief").expect("parse selector error");
-        if let Some(dom) = elm.select(&selector).next() {
-            post.desc(dom.text().collect::<String>().trim().into());
-        }
+        match Field::new("BoardPost::desc", ".b-list__brief").text(&elm) {
+            Ok(desc) => post.desc(desc),
+            Err(e) => post.parse_error(e),
+        };
 
         // gp
-        let selector = Selector::parse(".b-list__summary__gp").expect("parse selector error");
-        if let Some(dom) = elm.select(&selector).next() {
-            let text: String = dom.text().collect::<String>().trim().into();
-            post.gp(text.parse::<u16>().unwrap());
-        }
+        let field = Field::new("BoardPost::gp", ".b-list__summary__gp");
+        match field
+            .text(&elm)
+            .and_then(|text| BoardPost::try_gp_from_text(&field, &text))
+        {
+            Ok(gp) => post.gp(gp),
+            Err(e) => post.parse_error(e),
+        };
 
         // reply
-        let selector =
-            Selector::parse(".b-list__count__number span").expect("parse selector error");
-        if let Some(dom) = elm.select(&selector).next() {
-            let text: String = dom.text().collect::<String>().trim().into();
-            post.reply_count(text.parse::<u16>().unwrap());
-        }
+        let field = Field::new("BoardPost::reply", ".b-list__count__number span");
+        match field.text(&elm).and_then(|text| field.number::<u16>(&text)) {
+            Ok(reply) => post.reply_count(reply),
+            Err(e) => post.parse_error(e),
+        };
+
+        // author
+        match Field::new("BoardPost::author", ".b-list__count__user a").text(&elm) {
+            Ok(author) => post.author(author),
+            Err(e) => post.parse_error(e),
+        };
 
         // date
-        let selector = Selector::parse(".b-list__time__edittime a").expect("parse selector error");
-        if let Some(dom) = elm.select(&selector).next() {
-            let text: String = dom.text().collect::<String>().trim().into();
-            post.date(text);
-        }
+        match Field::new("BoardPost::date", ".b-list__time__edittime a").text(&elm) {
+            Ok(date) => post.date(Timestamp::parse(date.as_ref())),
+            Err(e) => post.parse_error(e),
+        };
 
         // category
-        let selector = Selector::parse(".b-list__summary__sort a").expect("parse selector error");
-        if let Some(dom) = elm.select(&selector).next() {
-            let name = dom.text().collect::<String>();
-            let href = dom.value().attr("href").unwrap();
-            let url =
-                Url::parse(format!("{}/{}", DN, href).as_str()).expect("invalid category url");
-
-            post.category(BoardCategory {
-                name,
-                id: BoardCategoryId::try_from(url).expect("invalid category url"),
-            });
-        }
+        match BoardPost::try_category_from_html(&elm, base) {
+            Ok(category) => post.category(category),
+            Err(e) => post.parse_error(e),
+        };
 
         Ok(post)
     }
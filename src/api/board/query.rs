@@ -0,0 +1,75 @@
+use url::Url;
+
+use super::post::BoardPost;
+
+/// Search a board by title keyword, author id or both.
+///
+/// The site searches one field at a time, when both are given the title is
+/// searched by the site and the author is filtered from the results.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct BoardPostQuery {
+    pub title: Option<String>,
+    pub author: Option<String>,
+}
+
+impl BoardPostQuery {
+    pub fn title(keyword: &str) -> BoardPostQuery {
+        BoardPostQuery {
+            title: Some(keyword.to_string()),
+            author: None,
+        }
+    }
+
+    pub fn author(id: &str) -> BoardPostQuery {
+        BoardPostQuery {
+            title: None,
+            author: Some(id.to_string()),
+        }
+    }
+
+    pub fn with_title(mut self, keyword: &str) -> BoardPostQuery {
+        self.title = Some(keyword.to_string());
+        self
+    }
+
+    pub fn with_author(mut self, id: &str) -> BoardPostQuery {
+        self.author = Some(id.to_string());
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.title.is_none() && self.author.is_none()
+    }
+
+    /// Whether `post` matches every field of the query.
+    pub fn matches(&self, post: &BoardPost) -> bool {
+        let title = self
+            .title
+            .as_ref()
+            .is_none_or(|t| post.title.to_lowercase().contains(&t.to_lowercase()));
+        let author = self
+            .author
+            .as_ref()
+            .is_none_or(|a| post.author.eq_ignore_ascii_case(a));
+
+        title && author
+    }
+
+    /// Author left to filter once the site searched the title.
+    pub(crate) fn client_author(&self) -> Option<&str> {
+        self.title.as_ref().and(self.author.as_deref())
+    }
+
+    /// Add the `qt` (1 title, 6 author) and `q` queries of `B.php`.
+    pub(crate) fn append_to(&self, url: &mut Url) {
+        let (qt, q) = match (&self.title, &self.author) {
+            (Some(title), _) => ("1", title),
+            (None, Some(author)) => ("6", author),
+            (None, None) => return,
+        };
+
+        url.query_pairs_mut()
+            .append_pair("qt", qt)
+            .append_pair("q", q);
+    }
+}
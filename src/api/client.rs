@@ -0,0 +1,304 @@
+use std::{sync::Arc, time::Duration};
+
+use reqwest::{
+    header::{HeaderMap, HeaderName, HeaderValue, USER_AGENT},
+    Proxy,
+};
+use serde_json::Value;
+use url::Url;
+
+use super::{
+    DiskCache, Error, RateLimit, ReqwestTransport, RetryPolicy, Session, Transport, API_DN,
+    AVATAR_DN, DN, HOME_DN,
+};
+
+static LOGIN_PATH: &str = "mobile_app/user/v3/do_login.php";
+
+/// Handle used by every request in [`crate::api`].
+///
+/// Cloning is cheap, all clones share the same connection pool.
+#[derive(Clone)]
+pub struct BahamutClient {
+    base: Url,
+    api: Url,
+    home: Url,
+    avatar: Url,
+    session: Session,
+    transport: Arc<dyn Transport>,
+}
+
+impl BahamutClient {
+    /// Client pointing to the forum with the default settings.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the underlying TLS backend cannot be initialized,
+    /// use [`BahamutClient::builder`] to handle this case.
+    pub fn new() -> BahamutClient {
+        BahamutClient::builder()
+            .build()
+            .expect("build default client fail")
+    }
+
+    pub fn builder() -> BahamutClientBuilder {
+        BahamutClientBuilder::default()
+    }
+
+    pub fn base_url(&self) -> &Url {
+        &self.base
+    }
+
+    /// Resolve `path` (for example `B.php?bsn=60076`) against the base url.
+    pub fn url(&self, path: &str) -> Url {
+        self.base.join(path).expect("invalid url")
+    }
+
+    /// Resolve `path` against the api url, used for login.
+    pub fn api_url(&self, path: &str) -> Url {
+        self.api.join(path).expect("invalid url")
+    }
+
+    /// Resolve `path` against the url of the user homes.
+    pub fn home_url(&self, path: &str) -> Url {
+        self.home.join(path).expect("invalid url")
+    }
+
+    /// Resolve `path` against the url of the avatar pictures.
+    pub fn avatar_url(&self, path: &str) -> Url {
+        self.avatar.join(path).expect("invalid url")
+    }
+
+    pub fn transport(&self) -> &dyn Transport {
+        self.transport.as_ref()
+    }
+
+    pub fn session(&self) -> &Session {
+        &self.session
+    }
+
+    /// Log in with account and password, the login cookies are stored
+    /// in [`BahamutClient::session`].
+    ///
+    /// Custom transports don't share the session, the login will fail with
+    /// [`Error::Auth`] unless they set the cookies themselves.
+    pub async fn login(&self, username: &str, password: &str) -> Result<(), Error> {
+        let url = self.api_url(LOGIN_PATH);
+        // the app api requires a `vcode` field, it isn't a captcha answer but
+        // the constant the official mobile app always sends
+        let form = [("uid", username), ("passwd", password), ("vcode", "7045")];
+        let body = self.transport.post_form(&url, &form).await?;
+        let res = serde_json::from_str::<Value>(body.as_ref())?;
+
+        if res.get("userid").is_none() {
+            let msg = res
+                .pointer("/error/message")
+                .or_else(|| res.get("message"))
+                .and_then(Value::as_str)
+                .unwrap_or("login fail");
+            return Err(Error::Auth(msg.to_string()));
+        }
+
+        if !self.session.is_logged_in() {
+            return Err(Error::Auth("no login cookie in response".to_string()));
+        }
+
+        Ok(())
+    }
+
+    pub fn logout(&self) {
+        self.session.clear();
+    }
+}
+
+impl Default for BahamutClient {
+    fn default() -> Self {
+        BahamutClient::new()
+    }
+}
+
+pub struct BahamutClientBuilder {
+    base_url: String,
+    api_url: String,
+    home_url: String,
+    avatar_url: String,
+    session: Session,
+    timeout: Duration,
+    user_agent: Option<String>,
+    headers: HeaderMap,
+    proxy: Option<Proxy>,
+    rate_limit: Option<RateLimit>,
+    retry: RetryPolicy,
+    disk_cache: Option<DiskCache>,
+    transport: Option<Arc<dyn Transport>>,
+}
+
+impl Default for BahamutClientBuilder {
+    fn default() -> Self {
+        BahamutClientBuilder {
+            base_url: DN.to_string(),
+            api_url: API_DN.to_string(),
+            home_url: HOME_DN.to_string(),
+            avatar_url: AVATAR_DN.to_string(),
+            session: Session::new(),
+            timeout: Duration::from_secs(5),
+            user_agent: None,
+            headers: HeaderMap::new(),
+            proxy: None,
+            rate_limit: None,
+            retry: RetryPolicy::default(),
+            disk_cache: None,
+            transport: None,
+        }
+    }
+}
+
+impl BahamutClientBuilder {
+    /// Root of the forum, defaults to [`DN`].
+    pub fn base_url(mut self, url: &str) -> Self {
+        self.base_url = url.to_string();
+        self
+    }
+
+    /// Root of the account api, defaults to [`API_DN`].
+    pub fn api_url(mut self, url: &str) -> Self {
+        self.api_url = url.to_string();
+        self
+    }
+
+    /// Root of the user homes, defaults to [`HOME_DN`].
+    pub fn home_url(mut self, url: &str) -> Self {
+        self.home_url = url.to_string();
+        self
+    }
+
+    /// Root of the avatar pictures, defaults to [`AVATAR_DN`].
+    pub fn avatar_url(mut self, url: &str) -> Self {
+        self.avatar_url = url.to_string();
+        self
+    }
+
+    /// Reuse a saved or shared session instead of an empty one.
+    pub fn session(mut self, session: Session) -> Self {
+        self.session = session;
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn user_agent(mut self, user_agent: &str) -> Self {
+        self.user_agent = Some(user_agent.to_string());
+        self
+    }
+
+    /// Headers sent with every request.
+    pub fn default_headers(mut self, headers: HeaderMap) -> Self {
+        self.headers = headers;
+        self
+    }
+
+    /// Add a header sent with every request, such as
+    /// `header(HeaderName::from_static("referer"), value)`.
+    pub fn header(mut self, name: HeaderName, value: HeaderValue) -> Self {
+        self.headers.insert(name, value);
+        self
+    }
+
+    pub fn proxy(mut self, proxy: Proxy) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    /// Limit the requests to each host, unlimited by default.
+    pub fn rate_limit(mut self, rate_limit: RateLimit) -> Self {
+        self.rate_limit = Some(rate_limit);
+        self
+    }
+
+    /// Retries of failed page fetches, defaults to [`RetryPolicy::default`].
+    pub fn retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Keep fetched pages on disk, pages are only cached in memory by
+    /// [`CachedPage`](super::CachedPage) by default. The disk cache is
+    /// skipped while the session is logged in.
+    pub fn disk_cache(mut self, cache: DiskCache) -> Self {
+        self.disk_cache = Some(cache);
+        self
+    }
+
+    /// Fetch through `transport` instead of reqwest, timeout, user agent,
+    /// headers, proxy, rate limit, retries and disk cache only apply to the
+    /// default transport.
+    pub fn transport<T: Transport + 'static>(mut self, transport: T) -> Self {
+        self.transport = Some(Arc::new(transport));
+        self
+    }
+
+    pub fn build(self) -> Result<BahamutClient, Error> {
+        let base = BahamutClientBuilder::parse_root(self.base_url.as_ref())?;
+        let api = BahamutClientBuilder::parse_root(self.api_url.as_ref())?;
+        let home = BahamutClientBuilder::parse_root(self.home_url.as_ref())?;
+        let avatar = BahamutClientBuilder::parse_root(self.avatar_url.as_ref())?;
+        let session = self.session;
+
+        if let Some(transport) = self.transport {
+            return Ok(BahamutClient {
+                base,
+                api,
+                home,
+                avatar,
+                session,
+                transport,
+            });
+        }
+
+        let mut headers = self.headers;
+        if let Some(user_agent) = self.user_agent {
+            let user_agent = HeaderValue::from_str(user_agent.as_ref())
+                .map_err(|_| Error::Config(format!("invalid user agent {}", user_agent)))?;
+            headers.insert(USER_AGENT, user_agent);
+        }
+
+        let mut builder = reqwest::ClientBuilder::new()
+            .timeout(self.timeout)
+            .default_headers(headers)
+            .cookie_provider(Arc::new(session.clone()));
+
+        if let Some(proxy) = self.proxy {
+            builder = builder.proxy(proxy);
+        }
+
+        let mut transport = ReqwestTransport::new(builder.build()?)
+            .retry(self.retry)
+            .session(session.clone());
+        if let Some(rate_limit) = self.rate_limit {
+            transport = transport.rate_limit(rate_limit);
+        }
+        if let Some(cache) = self.disk_cache {
+            transport = transport.disk_cache(cache);
+        }
+
+        Ok(BahamutClient {
+            base,
+            api,
+            home,
+            avatar,
+            session,
+            transport: Arc::new(transport),
+        })
+    }
+
+    fn parse_root(url: &str) -> Result<Url, Error> {
+        // without the trailing slash `Url::join` would drop the last segment
+        if url.ends_with('/') {
+            Ok(Url::parse(url)?)
+        } else {
+            Ok(Url::parse(format!("{}/", url).as_ref())?)
+        }
+    }
+}
@@ -0,0 +1,386 @@
+//! Pages kept on disk by [`ReqwestTransport`](super::ReqwestTransport),
+//! see [`BahamutClientBuilder::disk_cache`](super::BahamutClientBuilder::disk_cache).
+
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use super::Error;
+
+/// Time to live of a thread url without a reply count, its page changes
+/// with every reply
+const UNCOUNTED_POST_TTL: Duration = Duration::from_secs(60);
+/// Entries unused for longer are removed, see [`DiskCache::max_age`]
+const DEFAULT_MAX_AGE: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+/// Seconds between two sweeps of old entries
+const SWEEP_INTERVAL: u64 = 60 * 60;
+
+/// Tells apart the temporary files of concurrent stores
+static TMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Kind of page, each one has its own time to live.
+///
+/// Only these pages are cached, account pages, forms and tokens never are.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CacheResource {
+    /// Forum front page
+    Front,
+    /// `searchb.php`
+    Search,
+    /// `B.php`, board pages and board info
+    Board,
+    /// `C.php`, the url has the reply count (`tnum`) so a new reply is a new
+    /// page. Urls without one live at most a minute.
+    Post,
+    /// `ajax/moreCommend.php`
+    Comment,
+    /// `homeindex.php`
+    User,
+}
+
+impl CacheResource {
+    pub fn from_url(url: &Url) -> Option<CacheResource> {
+        let name = url.path_segments()?.next_back().unwrap_or_default();
+
+        match name {
+            "" => Some(CacheResource::Front),
+            "searchb.php" => Some(CacheResource::Search),
+            "B.php" => Some(CacheResource::Board),
+            "C.php" => Some(CacheResource::Post),
+            "moreCommend.php" => Some(CacheResource::Comment),
+            "homeindex.php" => Some(CacheResource::User),
+            _ => None,
+        }
+    }
+
+    /// `tnum` is the reply count of the thread, 0 or missing when unknown.
+    fn has_reply_count(url: &Url) -> bool {
+        url.query_pairs()
+            .any(|(k, v)| k == "tnum" && v.parse::<u32>().is_ok_and(|n| n > 0))
+    }
+
+    fn default_ttl(&self) -> Duration {
+        match self {
+            CacheResource::Front => Duration::from_secs(10 * 60),
+            CacheResource::Search => Duration::from_secs(60 * 60),
+            CacheResource::Board => Duration::from_secs(60),
+            CacheResource::Post => Duration::from_secs(24 * 60 * 60),
+            CacheResource::Comment => Duration::from_secs(60),
+            CacheResource::User => Duration::from_secs(60 * 60),
+        }
+    }
+}
+
+/// Page bodies stored by url in `dir`.
+///
+/// A page younger than the time to live of its [`CacheResource`] is read
+/// from disk without any request. An older one is fetched again, with its
+/// `ETag` and `Last-Modified` so an unchanged page is answered with a 304.
+/// Pages not fetched nor revalidated for [`DiskCache::max_age`] are removed
+/// by a sweep at most once an hour.
+#[derive(Clone, Debug)]
+pub struct DiskCache {
+    dir: PathBuf,
+    ttl: HashMap<CacheResource, Duration>,
+    max_age: Duration,
+    /// Unix time of the last sweep, shared by the clones
+    swept: Arc<AtomicU64>,
+}
+
+impl DiskCache {
+    pub fn new<P: Into<PathBuf>>(dir: P) -> DiskCache {
+        DiskCache {
+            dir: dir.into(),
+            ttl: HashMap::new(),
+            max_age: DEFAULT_MAX_AGE,
+            swept: Arc::default(),
+        }
+    }
+
+    /// Cache in `bahamut/http` of the user cache dir
+    /// (`$XDG_CACHE_HOME` or `~/.cache` on Linux).
+    pub fn in_cache_dir() -> Option<DiskCache> {
+        dirs::cache_dir().map(|dir| DiskCache::new(dir.join("bahamut").join("http")))
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Time to live of `resource`, zero revalidates on every request.
+    pub fn ttl(mut self, resource: CacheResource, ttl: Duration) -> Self {
+        self.ttl.insert(resource, ttl);
+        self
+    }
+
+    /// Age after which an unused page is removed, a week by default.
+    pub fn max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = max_age;
+        self
+    }
+
+    /// Remove every cached page.
+    pub fn clear(&self) -> Result<(), Error> {
+        match std::fs::remove_dir_all(&self.dir) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    pub(crate) fn is_cached(&self, url: &Url) -> bool {
+        CacheResource::from_url(url).is_some()
+    }
+
+    pub(crate) fn is_fresh(&self, url: &Url, entry: &CacheEntry) -> bool {
+        let resource = match CacheResource::from_url(url) {
+            Some(resource) => resource,
+            None => return false,
+        };
+
+        let mut ttl = self
+            .ttl
+            .get(&resource)
+            .copied()
+            .unwrap_or_else(|| resource.default_ttl());
+        if resource == CacheResource::Post && !CacheResource::has_reply_count(url) {
+            ttl = ttl.min(UNCOUNTED_POST_TTL);
+        }
+
+        now().saturating_sub(entry.fetched) < ttl.as_secs()
+    }
+
+    /// Stored page of `url`, unreadable entries are treated as missing.
+    pub(crate) async fn load(&self, url: &Url) -> Option<CacheEntry> {
+        let json = tokio::fs::read_to_string(self.path(url)).await.ok()?;
+        let entry = serde_json::from_str::<CacheEntry>(json.as_ref()).ok()?;
+
+        // two urls with the same hash
+        if entry.url == url.as_str() {
+            Some(entry)
+        } else {
+            None
+        }
+    }
+
+    /// Write the page, through a temporary file of its own so readers and
+    /// other writers never see a partial entry.
+    pub(crate) async fn store(&self, url: &Url, entry: &CacheEntry) -> Result<(), Error> {
+        let path = self.path(url);
+        let tmp = path.with_extension(format!(
+            "{}-{}.tmp",
+            std::process::id(),
+            TMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        let json = serde_json::to_string(entry)?;
+
+        tokio::fs::create_dir_all(&self.dir).await?;
+        tokio::fs::write(&tmp, json).await?;
+        if let Err(e) = tokio::fs::rename(&tmp, &path).await {
+            tokio::fs::remove_file(&tmp).await.unwrap_or(());
+            return Err(e.into());
+        }
+
+        let now = now();
+        let swept = self.swept.load(Ordering::Relaxed);
+        if now.saturating_sub(swept) >= SWEEP_INTERVAL
+            && self
+                .swept
+                .compare_exchange(swept, now, Ordering::Relaxed, Ordering::Relaxed)
+                .is_ok()
+        {
+            self.sweep().await?;
+        }
+        Ok(())
+    }
+
+    /// Remove the pages and leftover temporary files last written more
+    /// than [`DiskCache::max_age`] ago.
+    pub async fn sweep(&self) -> Result<(), Error> {
+        let mut entries = match tokio::fs::read_dir(&self.dir).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(e.into()),
+        };
+
+        while let Some(entry) = entries.next_entry().await? {
+            let age = entry
+                .metadata()
+                .await
+                .and_then(|meta| meta.modified())
+                .ok()
+                .and_then(|modified| modified.elapsed().ok())
+                .unwrap_or_default();
+
+            if age > self.max_age {
+                // another client may have removed it already
+                tokio::fs::remove_file(entry.path()).await.unwrap_or(());
+            }
+        }
+        Ok(())
+    }
+
+    fn path(&self, url: &Url) -> PathBuf {
+        self.dir.join(format!("{:016x}.json", fnv1a(url.as_str())))
+    }
+}
+
+/// Stable across builds, unlike the std hasher.
+fn fnv1a(text: &str) -> u64 {
+    text.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub(crate) struct CacheEntry {
+    pub url: String,
+    /// Unix time of the last fetch or revalidation
+    pub fetched: u64,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    pub body: String,
+}
+
+impl CacheEntry {
+    pub(crate) fn new(
+        url: &Url,
+        body: String,
+        etag: Option<String>,
+        last_modified: Option<String>,
+    ) -> CacheEntry {
+        CacheEntry {
+            url: url.to_string(),
+            fetched: now(),
+            etag,
+            last_modified,
+            body,
+        }
+    }
+
+    /// Same page confirmed by a 304.
+    pub(crate) fn revalidated(mut self) -> CacheEntry {
+        self.fetched = now();
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Empty cache dir of its own for each test.
+    fn cache(name: &str) -> DiskCache {
+        let dir = std::env::temp_dir().join(format!(
+            "bahamut-disk-cache-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let cache = DiskCache::new(dir);
+        cache.clear().unwrap();
+        cache
+    }
+
+    fn url(path: &str) -> Url {
+        Url::parse("https://forum.gamer.com.tw/")
+            .unwrap()
+            .join(path)
+            .unwrap()
+    }
+
+    fn entry(url: &Url, age: u64) -> CacheEntry {
+        let mut entry = CacheEntry::new(url, "body".to_string(), None, None);
+        entry.fetched -= age;
+        entry
+    }
+
+    #[test]
+    fn fresh_by_resource() {
+        let cache = DiskCache::new("unused").ttl(CacheResource::Board, Duration::from_secs(5));
+        let board = url("B.php?bsn=60076");
+        let search = url("searchb.php?q=a");
+
+        assert!(cache.is_fresh(&board, &entry(&board, 0)));
+        assert!(!cache.is_fresh(&board, &entry(&board, 10)));
+        assert!(cache.is_fresh(&search, &entry(&search, 10)));
+        assert!(!cache.is_fresh(&url("login.php"), &entry(&board, 0)));
+    }
+
+    #[test]
+    fn threads_without_reply_count_are_short_lived() {
+        let cache = DiskCache::new("unused");
+
+        for (path, fresh) in [
+            ("C.php?bsn=60076&snA=1&tnum=5", true),
+            ("C.php?bsn=60076&snA=1&tnum=0", false),
+            ("C.php?bsn=60076&snA=1", false),
+        ] {
+            let url = url(path);
+            assert_eq!(cache.is_fresh(&url, &entry(&url, 120)), fresh, "{}", path);
+        }
+    }
+
+    #[tokio::test]
+    async fn load_checks_the_url() {
+        let cache = cache("collision");
+        let a = url("B.php?bsn=1");
+        let b = url("B.php?bsn=2");
+
+        cache.store(&a, &entry(&a, 0)).await.unwrap();
+        assert_eq!(cache.load(&a).await.map(|e| e.url), Some(a.to_string()));
+
+        // as if `b` had the hash of `a`
+        tokio::fs::copy(cache.path(&a), cache.path(&b))
+            .await
+            .unwrap();
+        assert!(cache.load(&b).await.is_none());
+
+        cache.clear().unwrap();
+    }
+
+    #[tokio::test]
+    async fn concurrent_stores() {
+        let cache = cache("concurrent");
+        let a = url("B.php?bsn=1");
+
+        let entries = (0..8).map(|_| entry(&a, 0)).collect::<Vec<CacheEntry>>();
+        let stores = entries.iter().map(|entry| cache.store(&a, entry));
+        for res in futures::future::join_all(stores).await {
+            res.unwrap();
+        }
+        assert!(cache.load(&a).await.is_some());
+
+        cache.clear().unwrap();
+    }
+
+    #[tokio::test]
+    async fn sweep_old_entries() {
+        let cache = cache("sweep");
+        let a = url("B.php?bsn=1");
+        cache.store(&a, &entry(&a, 0)).await.unwrap();
+
+        cache.sweep().await.unwrap();
+        assert!(cache.load(&a).await.is_some());
+
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        cache.clone().max_age(Duration::ZERO).sweep().await.unwrap();
+        assert!(cache.load(&a).await.is_none());
+
+        cache.clear().unwrap();
+    }
+}
@@ -0,0 +1,104 @@
+use std::fmt::Display;
+
+use super::ParseError;
+
+#[derive(Debug)]
+pub enum Error {
+    /// Connection refused, dns failure, broken body...
+    Network(reqwest::Error),
+    /// The server answered with a non success status code
+    Status(u16),
+    Timeout,
+    /// The response isn't the json we expect
+    Json(serde_json::Error),
+    /// The page doesn't look like what the parser expects,
+    /// usually means the site layout has changed
+    Layout(ParseError),
+    /// Board, post or page doesn't exist or has been deleted
+    NotFound,
+    /// Wrong account or password, or the session has expired
+    Auth(String),
+    /// Posting or voting too often, wait before sending again
+    FloodControl(String),
+    /// Logged in but not allowed, such as editing the comment of someone
+    /// else or posting in a locked thread
+    PermissionDenied(String),
+    /// The forum refused a form for another reason, such as an empty content
+    Rejected(String),
+    InvalidUrl(url::ParseError),
+    /// Reading or writing local files such as a saved [`Session`](crate::api::Session)
+    Io(std::io::Error),
+    /// Invalid [`BahamutClientBuilder`](crate::api::BahamutClientBuilder) settings
+    Config(String),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Network(e) => write!(f, "network error: {}", e),
+            Error::Status(code) => write!(f, "http status {}", code),
+            Error::Timeout => write!(f, "request timeout"),
+            Error::Json(e) => write!(f, "invalid json: {}", e),
+            Error::Layout(e) => write!(f, "unexpected page layout: {}", e),
+            Error::NotFound => write!(f, "not found or deleted"),
+            Error::Auth(msg) => write!(f, "authentication failed: {}", msg),
+            Error::FloodControl(msg) => write!(f, "sending too fast: {}", msg),
+            Error::PermissionDenied(msg) => write!(f, "permission denied: {}", msg),
+            Error::Rejected(msg) => write!(f, "rejected: {}", msg),
+            Error::InvalidUrl(e) => write!(f, "invalid url: {}", e),
+            Error::Io(e) => write!(f, "io error: {}", e),
+            Error::Config(msg) => write!(f, "invalid client config: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Network(e) => Some(e),
+            Error::Json(e) => Some(e),
+            Error::Layout(e) => Some(e),
+            Error::InvalidUrl(e) => Some(e),
+            Error::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for Error {
+    fn from(e: reqwest::Error) -> Self {
+        if e.is_timeout() {
+            Error::Timeout
+        } else if let Some(status) = e.status() {
+            Error::Status(status.as_u16())
+        } else if e.is_builder() {
+            Error::Config(e.to_string())
+        } else {
+            Error::Network(e)
+        }
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(e: serde_json::Error) -> Self {
+        Error::Json(e)
+    }
+}
+
+impl From<ParseError> for Error {
+    fn from(e: ParseError) -> Self {
+        Error::Layout(e)
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+impl From<url::ParseError> for Error {
+    fn from(e: url::ParseError) -> Self {
+        Error::InvalidUrl(e)
+    }
+}
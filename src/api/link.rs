@@ -0,0 +1,204 @@
+//! Boards and threads read from plain links, for pages listing them without
+//! the board list markup such as the front page or the account pages.
+//!
+//! Only the links of a [`section`] are read, the whole page also has the
+//! navigation, ads and footer links.
+
+use scraper::{ElementRef, Html};
+use url::Url;
+
+use super::{
+    board::{BoardCategory, BoardCategoryId, BoardPost},
+    parse::Field,
+    search::SearchResult,
+    ParseError, ParseErrorKind,
+};
+
+/// Levels above a heading searched for the list it titles
+const SECTION_DEPTH: usize = 4;
+
+/// Element holding a list, the first match of `field`, or else the closest
+/// container of a heading reading one of `titles` which has `links`.
+pub(crate) fn section<'a>(
+    document: &'a Html,
+    field: Field,
+    titles: &[&str],
+    links: Field,
+) -> Result<ElementRef<'a>, ParseError> {
+    let root = document.root_element();
+    if let Ok(section) = field.first(&root) {
+        return Ok(section);
+    }
+
+    let is_heading = |e: &ElementRef| {
+        !matches!(e.value().name(), "a" | "title" | "script" | "style")
+            && e.children()
+                .filter_map(|node| node.value().as_text())
+                .any(|text| titles.iter().any(|title| text.contains(title)))
+    };
+
+    root.descendants()
+        .filter_map(ElementRef::wrap)
+        .filter(is_heading)
+        .find_map(|heading| {
+            std::iter::once(heading)
+                .chain(heading.ancestors().filter_map(ElementRef::wrap))
+                .take(SECTION_DEPTH + 1)
+                // the whole page has every link
+                .take_while(|e| !matches!(e.value().name(), "body" | "html"))
+                .find(|e| links.first(e).is_ok())
+        })
+        .ok_or_else(|| field.error(ParseErrorKind::Missing))
+}
+
+/// Rows of a list in `section`, rows inside another row are part of it.
+pub(crate) fn rows<'a>(
+    section: &ElementRef<'a>,
+    field: Field,
+) -> Result<Vec<ElementRef<'a>>, ParseError> {
+    let all = field.all(section)?;
+    let rows = all
+        .iter()
+        .filter(|row| {
+            !row.ancestors()
+                .any(|parent| all.iter().any(|other| other.id() == parent.id()))
+        })
+        .copied()
+        .collect::<Vec<ElementRef>>();
+
+    if rows.is_empty() {
+        Err(field.error(ParseErrorKind::Missing))
+    } else {
+        Ok(rows)
+    }
+}
+
+struct Link {
+    text: String,
+    url: Url,
+}
+
+/// Links of `field` with a text, the first of each url is kept.
+fn links_from_html(root: &ElementRef, base: &Url, field: Field) -> Result<Vec<Link>, ParseError> {
+    let mut links: Vec<Link> = vec![];
+
+    field.all(root)?.into_iter().for_each(|a| {
+        let text = a.text().collect::<String>().trim().to_string();
+        let url = field
+            .attr(&a, "href")
+            .and_then(|href| field.url(base, href));
+
+        if let (false, Ok(url)) = (text.is_empty(), url) {
+            if !links.iter().any(|link| link.url == url) {
+                links.push(Link { text, url });
+            }
+        }
+    });
+
+    Ok(links)
+}
+
+/// Links of boards.
+pub(crate) const BOARD_LINKS: &str = "a[href*=\"B.php?bsn=\"]";
+/// Links of threads.
+pub(crate) const POST_LINKS: &str = "a[href*=\"C.php?\"]";
+
+/// Boards linked as `B.php?bsn=` in `root`, in page order without duplicates.
+pub(crate) fn boards_from_html(
+    root: &ElementRef,
+    base: &Url,
+    name: &'static str,
+) -> Result<Vec<SearchResult>, ParseError> {
+    let field = Field::new(name, BOARD_LINKS);
+    let id = Field::new(name, "bsn");
+
+    let boards = links_from_html(root, base, field)?
+        .into_iter()
+        .filter_map(|link| {
+            let id = id.query(&link.url).ok()?;
+            Some(SearchResult {
+                id,
+                name: link.text,
+                platform: String::new(),
+            })
+        })
+        .fold(vec![], |mut boards: Vec<SearchResult>, board| {
+            if !boards.iter().any(|b| b.id == board.id) {
+                boards.push(board);
+            }
+            boards
+        });
+
+    Ok(boards)
+}
+
+/// Threads linked as `C.php?bsn=&snA=` in `root`, only the id, title, url
+/// and board are known. The board id is in [`BoardCategoryId::id`].
+pub(crate) fn posts_from_html(
+    root: &ElementRef,
+    base: &Url,
+    name: &'static str,
+) -> Result<Vec<BoardPost>, ParseError> {
+    let field = Field::new(name, POST_LINKS);
+    let id = Field::new(name, "snA");
+
+    let posts = links_from_html(root, base, field)?
+        .into_iter()
+        .filter_map(|link| {
+            let board = BoardCategoryId::from(link.url.clone());
+            if board.id.is_empty() {
+                return None;
+            }
+
+            let mut post = BoardPost::default();
+            post.id(id.query(&link.url).ok()?);
+            post.title(link.text);
+            post.category(BoardCategory {
+                name: String::new(),
+                id: board,
+            });
+            post.url = link.url.to_string();
+            Some(post)
+        })
+        .collect();
+
+    Ok(posts)
+}
+
+/// The first board linked in each of `rows`, rows without one are skipped.
+pub(crate) fn boards_from_rows(
+    rows: &[ElementRef],
+    base: &Url,
+    name: &'static str,
+) -> Result<Vec<SearchResult>, ParseError> {
+    let mut boards: Vec<SearchResult> = vec![];
+
+    for row in rows {
+        if let Some(board) = boards_from_html(row, base, name)?.into_iter().next() {
+            if !boards.iter().any(|b| b.id == board.id) {
+                boards.push(board);
+            }
+        }
+    }
+
+    Ok(boards)
+}
+
+/// The first thread linked in each of `rows`, rows without one are skipped.
+pub(crate) fn posts_from_rows(
+    rows: &[ElementRef],
+    base: &Url,
+    name: &'static str,
+) -> Result<Vec<BoardPost>, ParseError> {
+    let mut posts: Vec<BoardPost> = vec![];
+
+    for row in rows {
+        if let Some(post) = posts_from_html(row, base, name)?.into_iter().next() {
+            if !posts.iter().any(|p| p.url == post.url) {
+                posts.push(post);
+            }
+        }
+    }
+
+    Ok(posts)
+}
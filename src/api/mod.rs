@@ -1,41 +1,75 @@
-use lazy_static::lazy_static;
+use std::collections::HashMap;
 
-use std::{collections::HashMap, time::Duration};
-
-use futures::executor::block_on;
+use async_trait::async_trait;
 use scraper::Html;
 use serde::de::DeserializeOwned;
 use url::Url;
 
+pub mod account;
+#[cfg(feature = "blocking")]
+pub mod blocking;
 pub mod board;
+mod client;
+mod disk_cache;
+mod error;
+mod link;
+mod parse;
 pub mod post;
 pub mod search;
+mod session;
+mod throttle;
+pub mod time;
+mod transport;
 pub mod user;
+mod write;
+
+pub use client::{BahamutClient, BahamutClientBuilder};
+pub use disk_cache::{CacheResource, DiskCache};
+pub use error::Error;
+pub use parse::{ParseError, ParseErrorKind};
+pub use session::{Session, COOKIE_DOMAIN, COOKIE_TOKEN, COOKIE_USER_ID};
+pub use throttle::{RateLimit, RetryPolicy};
+pub use time::Timestamp;
+pub use transport::{ReqwestTransport, Transport};
+
+use parse::Field;
 
 pub static DN: &str = "https://forum.gamer.com.tw/";
+pub static API_DN: &str = "https://api.gamer.com.tw/";
+pub static HOME_DN: &str = "https://home.gamer.com.tw/";
+pub static AVATAR_DN: &str = "https://avatar2.bahamut.com.tw/";
 
-lazy_static! {
-    static ref HTTP_CLIENT: reqwest::Client = reqwest::ClientBuilder::new()
-        .timeout(Duration::from_secs(5))
-        .build()
-        .unwrap();
+async fn get_text(client: &BahamutClient, url: &Url) -> Result<String, Error> {
+    client.transport().get_text(url).await
 }
 
-async fn get_document(url: &Url) -> Result<Html, Box<dyn std::error::Error>> {
-    let html = HTTP_CLIENT.get(url.as_str()).send().await?.text().await?;
+/// Like [`get_text`], without reading a cached copy of the page.
+async fn revalidate_text(client: &BahamutClient, url: &Url) -> Result<String, Error> {
+    client.transport().get_text_revalidate(url).await
+}
+
+async fn get_document(client: &BahamutClient, url: &Url) -> Result<Html, Error> {
+    let html = get_text(client, url).await?;
 
     Ok(Html::parse_document(html.as_ref()))
 }
 
-async fn get_json<T: DeserializeOwned>(url: &Url) -> Result<T, Box<dyn std::error::Error>> {
-    let res = HTTP_CLIENT
-        .get(url.as_str())
-        .send()
-        .await?
-        .json::<T>()
-        .await?;
+async fn get_json<T: DeserializeOwned>(client: &BahamutClient, url: &Url) -> Result<T, Error> {
+    let json = client.transport().get_json(url).await?;
 
-    Ok(res)
+    Ok(serde_json::from_value::<T>(json)?)
+}
+
+/// The forum answers missing or deleted boards and posts with a normal page
+/// containing an error message, instead of a 404.
+fn is_missing_page(document: &Html) -> bool {
+    let body = Field::new("body", "body").first(&document.root_element());
+    body.is_ok_and(|body| {
+        let text = body.text().collect::<String>();
+        ["不存在", "已被刪除", "已刪除", "找不到"]
+            .iter()
+            .any(|msg| text.contains(msg))
+    })
 }
 
 pub struct WebSite {
@@ -43,23 +77,45 @@ pub struct WebSite {
     pub document: Html,
 }
 
+impl WebSite {
+    /// Page fetched by other means, `url` is where it comes from.
+    pub fn from_html(html: &str, url: &Url) -> WebSite {
+        WebSite {
+            url: url.clone(),
+            document: Html::parse_document(html),
+        }
+    }
+}
+
 pub trait UrlWithId<T> {
-    fn url(p: T) -> Url;
+    fn url(base: &Url, p: T) -> Url;
 }
 
-pub trait CachedPage<T>
+/// Paged resource with an in memory cache, [`BoardPage`](board::BoardPage)
+/// and [`PostPage`](post::PostPage).
+#[async_trait]
+pub trait CachedPage<T>: Send + Sync
 where
-    T: Sized + TryFrom<WebSite> + Clone,
+    T: Sized + TryFrom<WebSite, Error = Error> + Clone + Send + Sync,
 {
-    fn cache(&self) -> &HashMap<u16, Option<T>>;
-    fn insert_cache(&mut self, page: &u16, obj: Option<T>);
+    fn client(&self) -> &BahamutClient;
+    fn cache(&self) -> &HashMap<u16, T>;
+    fn insert_cache(&mut self, page: &u16, obj: T);
     fn url(&self, page: &u16) -> Url;
     fn page(&self) -> u16;
     fn increase_page(&mut self);
     fn decrease_page(&mut self);
     fn max(&self) -> u16;
 
-    fn cached_page_html(&self, _: u16) -> Option<Html> {
+    /// Fetch the first page and read the page count from it.
+    async fn init(&mut self) -> Result<(), Error>;
+
+    /// Adjust a freshly parsed page before it's returned or cached.
+    fn prepare(&self, obj: T) -> T {
+        obj
+    }
+
+    fn cached_page_html(&self, _: u16) -> Option<String> {
         None
     }
 
@@ -72,93 +128,93 @@ where
         max != 0 && self.page() > max
     }
 
-    fn get_page_html(&self, page: u16) -> Option<Html> {
+    async fn get_page_html(&self, page: u16) -> Result<String, Error> {
         let url = self.url(&page);
-        block_on(get_document(&url)).ok()
+        get_text(self.client(), &url).await
     }
 
-    fn get(&self, page: u16, ignore_cache: bool) -> Option<T> {
+    async fn get(&self, page: u16, ignore_cache: bool) -> Result<T, Error> {
         let max = self.max();
         if max != 0 && page > max {
-            return None;
+            return Err(Error::NotFound);
         }
 
-        let cache = self.cache();
-        if !ignore_cache && cache.contains_key(&page) {
-            return cache.get(&page).unwrap().as_ref().cloned();
+        if !ignore_cache {
+            if let Some(cached) = self.cache().get(&page) {
+                return Ok(cached.clone());
+            }
         }
 
-        let document = if let Some(v) = self.cached_page_html(page) {
-            Some(v)
+        // ignoring the cache skips the disk cache of the transport too
+        let html = if ignore_cache {
+            revalidate_text(self.client(), &self.url(&page)).await?
         } else {
-            self.get_page_html(page)
-        };
-
-        if let Some(document) = document {
-            let url = self.url(&page);
-            if let Ok(board) = T::try_from(WebSite { url, document }) {
-                return Some(board);
+            match self.cached_page_html(page) {
+                Some(v) => v,
+                None => self.get_page_html(page).await?,
             }
-        }
+        };
 
-        None
+        let url = self.url(&page);
+        let obj = T::try_from(WebSite::from_html(html.as_ref(), &url))?;
+        Ok(self.prepare(obj))
     }
 
-    fn get_and_cache(&mut self, page: u16, ignore_cache: bool) -> Option<T> {
-        let result = self.get(page, ignore_cache);
+    async fn get_and_cache(&mut self, page: u16, ignore_cache: bool) -> Result<T, Error> {
+        let result = self.get(page, ignore_cache).await?;
         self.insert_cache(&page, result.clone());
-        result
+        Ok(result)
     }
 
-    fn get_current(&self) -> Option<T> {
-        self.get(self.page(), false)
+    async fn get_current(&self) -> Result<T, Error> {
+        self.get(self.page(), false).await
     }
 
-    fn get_current_and_cache(&mut self) -> Option<T> {
-        self.get_and_cache(self.page(), false)
+    async fn get_current_and_cache(&mut self) -> Result<T, Error> {
+        self.get_and_cache(self.page(), false).await
     }
 
-    fn get_current_force(&self) -> Option<T> {
-        self.get(self.page(), true)
+    async fn get_current_force(&self) -> Result<T, Error> {
+        self.get(self.page(), true).await
     }
 
-    fn get_current_force_and_cache(&mut self) -> Option<T> {
-        self.get_and_cache(self.page(), true)
+    async fn get_current_force_and_cache(&mut self) -> Result<T, Error> {
+        self.get_and_cache(self.page(), true).await
     }
 
-    fn next(&mut self) -> Option<T> {
+    async fn next(&mut self) -> Result<T, Error> {
         if self.is_over_max() {
-            None
+            Err(Error::NotFound)
         } else {
             self.increase_page();
-            self.get(self.page(), false)
+            self.get(self.page(), false).await
         }
     }
 
-    fn force_next(&mut self) -> Option<T> {
+    async fn force_next(&mut self) -> Result<T, Error> {
         if self.is_over_max() {
-            None
+            Err(Error::NotFound)
         } else {
             self.increase_page();
-            self.get(self.page(), true)
+            self.get(self.page(), true).await
         }
     }
 
-    fn previous(&mut self) -> Option<T> {
+    async fn previous(&mut self) -> Result<T, Error> {
         if self.is_over_min() {
-            None
+            Err(Error::NotFound)
         } else {
             self.decrease_page();
-            self.get(self.page(), false)
+            self.get(self.page(), false).await
         }
     }
 
-    fn force_previous(&mut self) -> Option<T> {
+    async fn force_previous(&mut self) -> Result<T, Error> {
         if self.is_over_min() {
-            None
+            Err(Error::NotFound)
         } else {
             self.decrease_page();
-            self.get(self.page(), true)
+            self.get(self.page(), true).await
         }
     }
 }
@@ -0,0 +1,120 @@
+use std::{fmt::Display, str::FromStr};
+
+use scraper::{ElementRef, Selector};
+use serde::Serialize;
+use url::Url;
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub enum ParseErrorKind {
+    /// The css selector itself is invalid
+    Selector,
+    /// Nothing matches the selector
+    Missing,
+    /// The element doesn't have this attribute
+    Attribute(&'static str),
+    /// The text isn't the expected number
+    Number(String),
+    /// The text isn't a valid url
+    Url(String),
+    /// No text of the selected element starts with this label,
+    /// as in `GP：123`
+    Label(&'static str),
+}
+
+/// A field which can't be read from the page.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ParseError {
+    /// For example `BoardPost::gp`
+    pub field: &'static str,
+    /// Css selector or url query key the field is read from
+    pub selector: &'static str,
+    pub kind: ParseErrorKind,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let reason = match &self.kind {
+            ParseErrorKind::Selector => "invalid selector".to_string(),
+            ParseErrorKind::Missing => "not found".to_string(),
+            ParseErrorKind::Attribute(name) => format!("missing attribute {}", name),
+            ParseErrorKind::Number(text) => format!("invalid number {:?}", text),
+            ParseErrorKind::Url(text) => format!("invalid url {:?}", text),
+            ParseErrorKind::Label(label) => format!("no text labeled {}", label),
+        };
+
+        write!(f, "{} ({}): {}", self.field, self.selector, reason)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Where a model field is read from, every helper reports failures with it.
+#[derive(Clone, Copy)]
+pub(crate) struct Field {
+    name: &'static str,
+    selector: &'static str,
+}
+
+impl Field {
+    pub(crate) const fn new(name: &'static str, selector: &'static str) -> Field {
+        Field { name, selector }
+    }
+
+    pub(crate) fn error(&self, kind: ParseErrorKind) -> ParseError {
+        ParseError {
+            field: self.name,
+            selector: self.selector,
+            kind,
+        }
+    }
+
+    pub(crate) fn selector(&self) -> Result<Selector, ParseError> {
+        Selector::parse(self.selector).map_err(|_| self.error(ParseErrorKind::Selector))
+    }
+
+    pub(crate) fn all<'a>(&self, elm: &ElementRef<'a>) -> Result<Vec<ElementRef<'a>>, ParseError> {
+        let selector = self.selector()?;
+        Ok(elm.select(&selector).collect())
+    }
+
+    pub(crate) fn first<'a>(&self, elm: &ElementRef<'a>) -> Result<ElementRef<'a>, ParseError> {
+        let selector = self.selector()?;
+        let first = elm.select(&selector).next();
+        first.ok_or_else(|| self.error(ParseErrorKind::Missing))
+    }
+
+    /// Trimmed text of the first match.
+    pub(crate) fn text(&self, elm: &ElementRef) -> Result<String, ParseError> {
+        let text = self.first(elm)?.text().collect::<String>();
+        Ok(text.trim().to_string())
+    }
+
+    pub(crate) fn attr<'a>(
+        &self,
+        elm: &ElementRef<'a>,
+        name: &'static str,
+    ) -> Result<&'a str, ParseError> {
+        elm.value()
+            .attr(name)
+            .ok_or_else(|| self.error(ParseErrorKind::Attribute(name)))
+    }
+
+    pub(crate) fn number<T: FromStr>(&self, text: &str) -> Result<T, ParseError> {
+        let text = text.trim();
+        text.parse::<T>()
+            .map_err(|_| self.error(ParseErrorKind::Number(text.to_string())))
+    }
+
+    pub(crate) fn url(&self, base: &Url, href: &str) -> Result<Url, ParseError> {
+        base.join(href)
+            .map_err(|_| self.error(ParseErrorKind::Url(href.to_string())))
+    }
+
+    /// Value of the `self.selector` query key.
+    pub(crate) fn query(&self, url: &Url) -> Result<String, ParseError> {
+        url.query_pairs()
+            .find(|(k, _)| k == self.selector)
+            .map(|(_, v)| v.to_string())
+            .ok_or_else(|| self.error(ParseErrorKind::Missing))
+    }
+}
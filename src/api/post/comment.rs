@@ -1,12 +1,13 @@
 use std::collections::HashMap;
 
-use futures::executor::block_on;
-use serde::{Deserialize, Serialize};
+use futures::{stream, Stream, TryStreamExt};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 
 use serde_json::Value;
-use url::Url;
 
-use crate::api::{get_json, DN};
+use crate::api::{get_json, BahamutClient, Error, Timestamp};
+
+static NEXT_KEY: &str = "next_snC";
 
 #[derive(Clone, Serialize, Deserialize)]
 pub struct PostComment {
@@ -14,40 +15,210 @@ pub struct PostComment {
     pub sn: String,
     pub userid: String,
     pub comment: String,
-    pub gp: String,
-    pub bp: String,
-    pub wtime: String,
-    pub mtime: String,
-    pub state: String,
+    #[serde(deserialize_with = "de_count")]
+    pub gp: u32,
+    #[serde(deserialize_with = "de_count")]
+    pub bp: u32,
+    /// Written at
+    pub wtime: Timestamp,
+    /// Last modified at
+    pub mtime: Timestamp,
+    pub state: CommentState,
     pub floor: u16,
     pub content: String,
-    pub time: String,
+    /// Display time
+    pub time: Timestamp,
     pub nick: String,
 
+    /// Fields the api sends which aren't modeled above
     #[serde(flatten)]
-    other: HashMap<String, serde_json::Value>,
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum CommentState {
+    Normal,
+    Deleted,
+    /// Hidden because of too many BP
+    Folded,
+    /// State code we don't know about
+    Other(String),
+}
+
+impl From<&str> for CommentState {
+    fn from(code: &str) -> Self {
+        match code {
+            "0" => CommentState::Normal,
+            "1" => CommentState::Deleted,
+            "2" => CommentState::Folded,
+            code => CommentState::Other(code.to_string()),
+        }
+    }
+}
+
+impl CommentState {
+    pub fn code(&self) -> &str {
+        match self {
+            CommentState::Normal => "0",
+            CommentState::Deleted => "1",
+            CommentState::Folded => "2",
+            CommentState::Other(code) => code.as_ref(),
+        }
+    }
+}
+
+impl Serialize for CommentState {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.code())
+    }
+}
+
+impl<'de> Deserialize<'de> for CommentState {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let code = match Value::deserialize(deserializer)? {
+            Value::String(s) => s,
+            Value::Number(n) => n.to_string(),
+            v => return Err(de::Error::custom(format!("invalid state {}", v))),
+        };
+
+        Ok(CommentState::from(code.as_ref()))
+    }
+}
+
+/// GP and BP are sent as strings, sometimes as numbers.
+fn de_count<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u32, D::Error> {
+    match Value::deserialize(deserializer)? {
+        Value::Number(n) => n
+            .as_u64()
+            .and_then(|n| u32::try_from(n).ok())
+            .ok_or_else(|| de::Error::custom(format!("invalid count {}", n))),
+        Value::String(s) if s.trim().is_empty() => Ok(0),
+        Value::String(s) => s
+            .trim()
+            .parse::<u32>()
+            .map_err(|_| de::Error::custom(format!("invalid count {:?}", s))),
+        v => Err(de::Error::custom(format!("invalid count {}", v))),
+    }
+}
+
+/// One response of `ajax/moreCommend.php`.
+#[derive(Clone)]
+pub struct PostCommentBatch {
+    pub comments: Vec<PostComment>,
+    /// `next_snC` cursor of the following batch, `None` when every comment is fetched
+    pub next: Option<String>,
 }
 
 impl PostComment {
-    pub fn get_comment(
+    pub fn is_deleted(&self) -> bool {
+        self.state == CommentState::Deleted
+    }
+
+    /// First batch of comments of the floor, use [`PostComment::get_all_comment`]
+    /// or [`PostComment::stream`] for the rest.
+    pub async fn get_comment(
+        client: &BahamutClient,
         id: String,
         c_id: String,
-    ) -> Result<Vec<PostComment>, Box<dyn std::error::Error>> {
-        let url = format!("{}ajax/moreCommend.php?bsn={}&snB={}", DN, id, c_id);
-        let url = Url::parse(url.as_ref()).unwrap();
-        let map = block_on(get_json::<HashMap<String, Value>>(&url))?;
+    ) -> Result<Vec<PostComment>, Error> {
+        let batch = PostComment::get_batch(client, id.as_ref(), c_id.as_ref(), None).await?;
+        Ok(batch.comments)
+    }
+
+    /// Batch of comments starting from the `next` cursor of the previous batch.
+    pub async fn get_batch(
+        client: &BahamutClient,
+        id: &str,
+        c_id: &str,
+        next: Option<&str>,
+    ) -> Result<PostCommentBatch, Error> {
+        let url = match next {
+            Some(next) => format!(
+                "ajax/moreCommend.php?bsn={}&snB={}&{}={}",
+                id, c_id, NEXT_KEY, next
+            ),
+            None => format!("ajax/moreCommend.php?bsn={}&snB={}", id, c_id),
+        };
+        let url = client.url(url.as_ref());
+        let map = get_json::<HashMap<String, Value>>(client, &url).await?;
 
+        PostComment::try_batch_from_map(map)
+    }
+
+    /// Every comment of the floor, following `next_snC` until it is exhausted.
+    pub fn stream(
+        client: &BahamutClient,
+        id: String,
+        c_id: String,
+    ) -> impl Stream<Item = Result<PostComment, Error>> + Send + 'static {
+        let client = client.clone();
+
+        // (cursor, is_first) the first batch is fetched without a cursor
+        stream::try_unfold((None::<String>, true), move |(next, first)| {
+            let client = client.clone();
+            let id = id.clone();
+            let c_id = c_id.clone();
+
+            async move {
+                if !first && next.is_none() {
+                    return Ok(None);
+                }
+
+                let batch =
+                    PostComment::get_batch(&client, id.as_ref(), c_id.as_ref(), next.as_deref())
+                        .await?;
+
+                // a cursor pointing to itself would never end
+                let following = batch.next.filter(|v| Some(v) != next.as_ref());
+                Ok::<_, Error>(Some((batch.comments, (following, false))))
+            }
+        })
+        .map_ok(|comments| stream::iter(comments.into_iter().map(Ok)))
+        .try_flatten()
+    }
+
+    /// Collect [`PostComment::stream`] sorted by floor.
+    pub async fn get_all_comment(
+        client: &BahamutClient,
+        id: String,
+        c_id: String,
+    ) -> Result<Vec<PostComment>, Error> {
+        let mut list = PostComment::stream(client, id, c_id)
+            .try_collect::<Vec<PostComment>>()
+            .await?;
+
+        list.sort_by_key(|v| v.floor);
+        list.dedup_by(|a, b| a.sn == b.sn);
+        Ok(list)
+    }
+
+    /// Parse a saved `ajax/moreCommend.php` response.
+    pub fn from_json(json: &str) -> Result<Vec<PostComment>, Error> {
+        let map = serde_json::from_str::<HashMap<String, Value>>(json)?;
+        PostComment::try_from_map(map)
+    }
+
+    fn try_batch_from_map(map: HashMap<String, Value>) -> Result<PostCommentBatch, Error> {
+        // sent as a number or a string, 0 when there is no more comment
+        let next = match map.get(NEXT_KEY) {
+            Some(Value::Number(n)) => Some(n.to_string()),
+            Some(Value::String(s)) => Some(s.to_owned()),
+            _ => None,
+        }
+        .filter(|v| !v.is_empty() && v != "0");
+
+        Ok(PostCommentBatch {
+            comments: PostComment::try_from_map(map)?,
+            next,
+        })
+    }
+
+    fn try_from_map(map: HashMap<String, Value>) -> Result<Vec<PostComment>, Error> {
         let mut list = map
             .iter()
-            .filter_map(|(k, v)| {
-                if k == "next_snC" {
-                    None
-                } else {
-                    let comment: PostComment = serde_json::from_value(v.clone()).unwrap();
-                    Some(comment)
-                }
-            })
-            .collect::<Vec<PostComment>>();
+            .filter(|(k, _)| *k != NEXT_KEY)
+            .map(|(_, v)| serde_json::from_value::<PostComment>(v.clone()))
+            .collect::<Result<Vec<PostComment>, serde_json::Error>>()?;
 
         list.sort_by_key(|v| v.floor);
         Ok(list)
@@ -1,9 +1,13 @@
-use scraper::{ElementRef, Selector};
+use scraper::ElementRef;
 use serde::Serialize;
 
-use crate::api::user::User;
+use crate::api::{parse::Field, user::User, Error, ParseError, ParseErrorKind, Timestamp};
 
-use super::{comment::PostComment, PostDescription};
+use super::{
+    comment::PostComment,
+    desc::{DescBuilder, TextStyle},
+    PostDescription,
+};
 
 pub trait CommentReadable {
     fn comment(&self) -> Vec<PostComment>;
@@ -15,7 +19,14 @@ pub struct PostContent {
     pub desc: PostDescription,
     pub user: User,
     pub floor: u16,
-    pub date: String,
+    pub date: Timestamp,
+    pub gp: u32,
+    pub bp: u32,
+    /// Floors quoted or referenced by this floor
+    pub replies_to: Vec<u16>,
+
+    /// Fields which can't be read and were left to their default
+    pub parse_errors: Vec<ParseError>,
 }
 
 impl CommentReadable for PostContent {
@@ -25,82 +36,118 @@ impl CommentReadable for PostContent {
 }
 
 impl PostContent {
-    pub fn try_floor_from_html(document: &ElementRef) -> Option<u16> {
-        let selector = Selector::parse(".floor").unwrap();
-        let floor = document
-            .select(&selector)
-            .next()
-            .unwrap()
+    pub fn try_floor_from_html(document: &ElementRef) -> Result<u16, ParseError> {
+        let field = Field::new("PostContent::floor", ".floor");
+        let floor = field.attr(&field.first(document)?, "data-floor")?;
+        field.number::<u16>(floor)
+    }
+
+    pub fn try_id_from_html(document: &ElementRef) -> Result<String, ParseError> {
+        let field = Field::new("PostContent::id", ".c-article");
+        let id = field
+            .first(document)?
             .value()
-            .attr("data-floor")
-            .unwrap()
-            .parse::<u16>()
-            .map_or(0u16, |v| v);
+            .id()
+            .ok_or_else(|| field.error(ParseErrorKind::Attribute("id")))?;
 
-        Some(floor)
+        Ok(id.replace("cf", ""))
     }
 
-    pub fn try_id_from_html(document: &ElementRef) -> Option<String> {
-        let selector = Selector::parse(".c-article").unwrap();
-        let id = document.select(&selector).next()?.value().id()?;
+    /// Broken images and videos are skipped and reported in `errors`.
+    pub fn try_desc_from_html(
+        document: &ElementRef,
+        errors: &mut Vec<ParseError>,
+    ) -> Result<PostDescription, ParseError> {
+        let content_field = Field::new("PostContent::desc", ".c-article__content");
 
-        Some(id.replace("cf", ""))
-    }
+        let mut builder = DescBuilder::new(errors);
+        content_field
+            .all(document)?
+            .iter()
+            .for_each(|el| builder.children(el, TextStyle::default()));
 
-    pub fn try_desc_from_html(document: &ElementRef) -> Option<PostDescription> {
-        let selector = Selector::parse(".c-article__content").unwrap();
-        let desc_selector = Selector::parse("div").unwrap();
-
-        let desc = document
-            .select(&selector)
-            .flat_map(|el| {
-                let content = el.select(&desc_selector);
-                let is_pure_text = content.clone().next().is_none();
-
-                if is_pure_text {
-                    return el
-                        .text()
-                        .map(|s| s.to_string())
-                        .collect::<PostDescription>();
-                }
-
-                content
-                    .flat_map(|el| {
-                        // youtube
-                        let yt_selector = Selector::parse(".video-youtube iframe").unwrap();
-                        let yt = el.select(&yt_selector).next();
-                        if yt.is_some() {
-                            return vec![yt.unwrap().value().attr("data-src").unwrap().to_string()];
-                        }
-
-                        // image
-                        let img_selector = Selector::parse("a img").unwrap();
-                        let img_dom = el.select(&img_selector);
-                        let img = img_dom.clone().next();
-                        if img.is_some() {
-                            return img_dom
-                                .map(|_img| _img.value().attr("data-src").unwrap().to_string())
-                                .collect::<Vec<String>>();
-                        }
-
-                        vec![el.text().collect::<String>()]
-                    })
-                    .collect::<PostDescription>()
-            })
-            .collect::<PostDescription>();
-
-        Some(desc)
+        Ok(builder.build())
     }
 
-    pub fn try_date_from_html(document: &ElementRef) -> Option<String> {
-        let selector = Selector::parse(".edittime").unwrap();
-        let date = document
-            .select(&selector)
-            .next()?
+    pub fn try_date_from_html(document: &ElementRef) -> Result<Timestamp, ParseError> {
+        let field = Field::new("PostContent::date", ".edittime");
+        let date = field
+            .first(document)?
             .text()
-            .next()?
-            .to_string();
+            .next()
+            .ok_or_else(|| field.error(ParseErrorKind::Missing))?;
+
+        Ok(Timestamp::parse(date))
+    }
+
+    /// `爆` is counted as 1000 like the board list, a hidden count (`-` or
+    /// `X`) as 0.
+    fn try_vote_from_html(document: &ElementRef, field: Field) -> Result<u32, ParseError> {
+        let text = field.text(document)?;
+        match text.as_ref() {
+            "" | "-" | "X" => Ok(0),
+            "爆" => Ok(1000),
+            _ => field.number::<u32>(&text),
+        }
+    }
+}
 
-        Some(date)
+/// Parse a floor (`.c-section[id]`), only a missing floor id fails the floor,
+/// other fields fall back to their default and are reported in
+/// [`PostContent::parse_errors`].
+impl TryFrom<&ElementRef<'_>> for PostContent {
+    type Error = Error;
+
+    fn try_from(dom: &ElementRef) -> Result<Self, Self::Error> {
+        let mut errors: Vec<ParseError> = vec![];
+        let id = PostContent::try_id_from_html(dom)?;
+
+        let desc = PostContent::try_desc_from_html(dom, &mut errors).unwrap_or_else(|e| {
+            errors.push(e);
+            PostDescription::default()
+        });
+
+        let user = User::try_from(dom).unwrap_or_else(|e| {
+            if let Error::Layout(e) = e {
+                errors.push(e);
+            }
+            User::default()
+        });
+
+        let floor = PostContent::try_floor_from_html(dom).unwrap_or_else(|e| {
+            errors.push(e);
+            0
+        });
+
+        let date = PostContent::try_date_from_html(dom).unwrap_or_else(|e| {
+            errors.push(e);
+            Timestamp::default()
+        });
+
+        let field = Field::new("PostContent::gp", ".postgp span");
+        let gp = PostContent::try_vote_from_html(dom, field).unwrap_or_else(|e| {
+            errors.push(e);
+            0
+        });
+
+        let field = Field::new("PostContent::bp", ".postbp span");
+        let bp = PostContent::try_vote_from_html(dom, field).unwrap_or_else(|e| {
+            errors.push(e);
+            0
+        });
+
+        let replies_to = PostContent::try_replies_from_desc(&desc, floor);
+
+        Ok(PostContent {
+            id,
+            desc,
+            user,
+            floor,
+            date,
+            gp,
+            bp,
+            replies_to,
+            parse_errors: errors,
+        })
     }
 }
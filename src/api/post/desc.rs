@@ -0,0 +1,401 @@
+use scraper::{node::Node, ElementRef};
+use serde::Serialize;
+
+use crate::api::{parse::Field, ParseError};
+
+use super::PostDescription;
+
+/// Block level content of a floor.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub enum Block {
+    Paragraph(Vec<Inline>),
+    Image(Image),
+    /// Embedded video, youtube and others
+    Video(String),
+    Quote(Vec<Block>),
+    /// Content hidden until clicked
+    Spoiler(Vec<Block>),
+    Rule,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub enum Inline {
+    Text(String, TextStyle),
+    Link { href: String, children: Vec<Inline> },
+    Break,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct TextStyle {
+    pub bold: bool,
+    pub italic: bool,
+    pub underline: bool,
+    pub strike: bool,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct Image {
+    /// Url of the image shown in the floor, usually a thumbnail
+    pub src: String,
+    /// Original size, `src` when the image isn't linked to it
+    pub original: String,
+    pub alt: String,
+}
+
+impl Block {
+    /// Text without any style, one line per paragraph line.
+    pub fn text(&self) -> String {
+        match self {
+            Block::Paragraph(inlines) => inlines.iter().map(Inline::text).collect(),
+            Block::Image(img) => img.original.to_owned(),
+            Block::Video(url) => url.to_owned(),
+            Block::Quote(blocks) | Block::Spoiler(blocks) => blocks
+                .iter()
+                .map(Block::text)
+                .collect::<Vec<String>>()
+                .join("\n"),
+            Block::Rule => String::new(),
+        }
+    }
+}
+
+impl TextStyle {
+    fn with_tag(self, tag: &str) -> TextStyle {
+        let mut style = self;
+        match tag {
+            "b" | "strong" => style.bold = true,
+            "i" | "em" => style.italic = true,
+            "u" | "ins" => style.underline = true,
+            "s" | "strike" | "del" => style.strike = true,
+            _ => {}
+        }
+        style
+    }
+}
+
+impl Inline {
+    pub fn text(&self) -> String {
+        match self {
+            Inline::Text(text, _) => text.to_owned(),
+            Inline::Link { children, .. } => children.iter().map(Inline::text).collect(),
+            Inline::Break => "\n".to_string(),
+        }
+    }
+}
+
+const IMAGE_FIELD: Field = Field::new("PostContent::desc", "img");
+const VIDEO_FIELD: Field = Field::new("PostContent::desc", "iframe");
+
+/// Walks the content of a floor, inline content is collected until a block
+/// element ends the paragraph.
+pub(super) struct DescBuilder<'e> {
+    blocks: PostDescription,
+    inlines: Vec<Inline>,
+    errors: &'e mut Vec<ParseError>,
+}
+
+impl<'e> DescBuilder<'e> {
+    pub(super) fn new(errors: &'e mut Vec<ParseError>) -> DescBuilder<'e> {
+        DescBuilder {
+            blocks: vec![],
+            inlines: vec![],
+            errors,
+        }
+    }
+
+    pub(super) fn build(mut self) -> PostDescription {
+        self.end_paragraph();
+        self.blocks
+    }
+
+    pub(super) fn children(&mut self, elm: &ElementRef, style: TextStyle) {
+        for node in elm.children() {
+            match node.value() {
+                Node::Text(text) => self.text(text, style),
+                Node::Element(_) => {
+                    if let Some(child) = ElementRef::wrap(node) {
+                        self.element(&child, style);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn element(&mut self, elm: &ElementRef, style: TextStyle) {
+        let name = elm.value().name();
+        let is_spoiler = elm.value().classes().any(|c| c.contains("spoiler"));
+
+        match name {
+            "br" => {
+                self.end_line();
+                self.inlines.push(Inline::Break);
+            }
+            "hr" => self.block(Block::Rule),
+            "img" => {
+                if let Some(img) = self.image(elm, None) {
+                    self.block(Block::Image(img));
+                }
+            }
+            "iframe" | "video" => {
+                // lazy loaded videos only have `data-src`
+                let src = VIDEO_FIELD
+                    .attr(elm, "data-src")
+                    .or_else(|_| VIDEO_FIELD.attr(elm, "src"));
+                match src {
+                    Ok(src) => self.block(Block::Video(src.to_string())),
+                    Err(e) => self.errors.push(e),
+                }
+            }
+            "script" | "style" | "noscript" => {}
+            _ if is_spoiler => {
+                let blocks = self.nested(elm, style);
+                self.block(Block::Spoiler(blocks));
+            }
+            "blockquote" => {
+                let blocks = self.nested(elm, style);
+                self.block(Block::Quote(blocks));
+            }
+            "a" => self.link(elm, style),
+            "b" | "strong" | "i" | "em" | "u" | "ins" | "s" | "strike" | "del" => {
+                self.children(elm, style.with_tag(name))
+            }
+            "div" | "p" | "li" | "ul" | "ol" | "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                self.end_paragraph();
+                self.children(elm, style);
+                self.end_paragraph();
+            }
+            _ => self.children(elm, style),
+        }
+    }
+
+    fn text(&mut self, text: &str, style: TextStyle) {
+        // line breaks in the source are only formatting, `<br>` breaks lines
+        let starts_with_line_break = text
+            .chars()
+            .take_while(|c| c.is_whitespace())
+            .any(|c| c == '\n');
+        let mut text = collapse_source_lines(text);
+        // indentation of the html source
+        if starts_with_line_break && self.is_line_start() {
+            text.remove(0);
+        }
+        if text.is_empty() {
+            return;
+        }
+
+        match self.inlines.last_mut() {
+            Some(Inline::Text(last, last_style)) if *last_style == style => last.push_str(&text),
+            _ => self.inlines.push(Inline::Text(text, style)),
+        }
+    }
+
+    fn is_line_start(&self) -> bool {
+        match self.inlines.last() {
+            None | Some(Inline::Break) => true,
+            Some(Inline::Text(last, _)) => last.ends_with(char::is_whitespace),
+            Some(Inline::Link { .. }) => false,
+        }
+    }
+
+    /// Drop the spaces left at the end of a line by the source line breaks.
+    fn end_line(&mut self) {
+        if let Some(Inline::Text(last, _)) = self.inlines.last_mut() {
+            last.truncate(last.trim_end_matches(' ').len());
+            if last.is_empty() {
+                self.inlines.pop();
+            }
+        }
+    }
+
+    fn link(&mut self, elm: &ElementRef, style: TextStyle) {
+        let href = elm.value().attr("href").unwrap_or_default().to_string();
+
+        // thumbnails link to the original size image
+        let images = elm
+            .descendants()
+            .filter_map(ElementRef::wrap)
+            .filter(|e| e.value().name() == "img")
+            .collect::<Vec<ElementRef>>();
+        if !images.is_empty() {
+            for img in images {
+                if let Some(img) = self.image(&img, Some(href.as_ref())) {
+                    self.block(Block::Image(img));
+                }
+            }
+            return;
+        }
+
+        let outer = std::mem::take(&mut self.inlines);
+        self.children(elm, style);
+        let children = std::mem::replace(&mut self.inlines, outer);
+        self.inlines.push(Inline::Link { href, children });
+    }
+
+    fn image(&mut self, elm: &ElementRef, href: Option<&str>) -> Option<Image> {
+        // images loaded eagerly only have `src`
+        let src = IMAGE_FIELD
+            .attr(elm, "data-src")
+            .or_else(|_| IMAGE_FIELD.attr(elm, "src"));
+
+        match src {
+            Ok(src) => Some(Image {
+                src: src.to_string(),
+                original: href.filter(|v| !v.is_empty()).unwrap_or(src).to_string(),
+                alt: elm.value().attr("alt").unwrap_or_default().to_string(),
+            }),
+            Err(e) => {
+                self.errors.push(e);
+                None
+            }
+        }
+    }
+
+    fn nested(&mut self, elm: &ElementRef, style: TextStyle) -> PostDescription {
+        let mut nested = DescBuilder::new(self.errors);
+        nested.children(elm, style);
+        nested.build()
+    }
+
+    fn block(&mut self, block: Block) {
+        self.end_paragraph();
+        self.blocks.push(block);
+    }
+
+    fn end_paragraph(&mut self) {
+        self.end_line();
+        if !self.inlines.is_empty() {
+            let inlines = std::mem::take(&mut self.inlines);
+            self.blocks.push(Block::Paragraph(inlines));
+        }
+    }
+}
+
+/// Every run of whitespace with a line break becomes one space, as in html.
+fn collapse_source_lines(text: &str) -> String {
+    fn push_run(collapsed: &mut String, run: &mut String) {
+        if run.contains('\n') {
+            collapsed.push(' ');
+        } else {
+            collapsed.push_str(run);
+        }
+        run.clear();
+    }
+
+    let mut collapsed = String::with_capacity(text.len());
+    let mut run = String::new();
+    for c in text.chars() {
+        if c.is_whitespace() {
+            run.push(c);
+        } else {
+            push_run(&mut collapsed, &mut run);
+            collapsed.push(c);
+        }
+    }
+    push_run(&mut collapsed, &mut run);
+
+    collapsed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use scraper::Html;
+
+    /// A floor as served in `.c-article__content`, images and videos are
+    /// lazy loaded.
+    const FLOOR: &str = r#"<div class="c-article__content">
+  <div>This is a long
+    English sentence that wraps <b>bold</b>
+    here.</div>
+  <div>第二段<br>
+    第二行 <a href="https://example.com/x" target="_blank">連結</a></div>
+  <div><a class="photoswipe-image" href="https://truth.bahamut.com.tw/s01/a.JPG"><img class="lazyload" data-src="https://truth.bahamut.com.tw/s01/a.JPG?w=300" src="data:image/gif;base64,R0lGODlhAQABAAAAACw=" alt="圖"></a></div>
+  <div class="video-youtube"><iframe class="lazyload" data-src="https://www.youtube.com/embed/abc" src="about:blank"></iframe></div>
+  <blockquote><div>引用
+    內容</div></blockquote>
+  <div class="spoiler"><div>雷</div></div>
+  <img src="https://i.example/eager.png">
+  <img class="broken">
+</div>"#;
+
+    fn text(text: &str) -> Inline {
+        Inline::Text(text.to_string(), TextStyle::default())
+    }
+
+    fn paragraph(text: &str) -> Block {
+        Block::Paragraph(vec![Inline::Text(text.to_string(), TextStyle::default())])
+    }
+
+    fn build(html: &str) -> (PostDescription, Vec<ParseError>) {
+        let html = Html::parse_fragment(html);
+        let mut errors = vec![];
+        let mut builder = DescBuilder::new(&mut errors);
+        builder.children(&html.root_element(), TextStyle::default());
+        (builder.build(), errors)
+    }
+
+    #[test]
+    fn desc_from_floor() {
+        let bold = TextStyle {
+            bold: true,
+            ..Default::default()
+        };
+        let (desc, errors) = build(FLOOR);
+
+        assert_eq!(
+            desc,
+            vec![
+                Block::Paragraph(vec![
+                    text("This is a long English sentence that wraps "),
+                    Inline::Text("bold".to_string(), bold),
+                    text(" here."),
+                ]),
+                Block::Paragraph(vec![
+                    text("第二段"),
+                    Inline::Break,
+                    text("第二行 "),
+                    Inline::Link {
+                        href: "https://example.com/x".to_string(),
+                        children: vec![text("連結")],
+                    },
+                ]),
+                Block::Image(Image {
+                    src: "https://truth.bahamut.com.tw/s01/a.JPG?w=300".to_string(),
+                    original: "https://truth.bahamut.com.tw/s01/a.JPG".to_string(),
+                    alt: "圖".to_string(),
+                }),
+                Block::Video("https://www.youtube.com/embed/abc".to_string()),
+                Block::Quote(vec![paragraph("引用 內容")]),
+                Block::Spoiler(vec![paragraph("雷")]),
+                Block::Image(Image {
+                    src: "https://i.example/eager.png".to_string(),
+                    original: "https://i.example/eager.png".to_string(),
+                    alt: String::new(),
+                }),
+            ]
+        );
+        assert_eq!(errors.len(), 1);
+    }
+
+    #[test]
+    fn desc_keeps_spaces_on_one_line() {
+        let (desc, _) = build("<div>a  b <i>c</i> d</div><div>\n  <br>\n  e\n</div>");
+
+        let italic = TextStyle {
+            italic: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            desc,
+            vec![
+                Block::Paragraph(vec![
+                    text("a  b "),
+                    Inline::Text("c".to_string(), italic),
+                    text(" d"),
+                ]),
+                Block::Paragraph(vec![Inline::Break, text("e")]),
+            ]
+        );
+    }
+}
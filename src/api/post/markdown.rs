@@ -0,0 +1,246 @@
+use super::{content::PostContent, post::Post, Block, Inline, TextStyle};
+
+impl PostContent {
+    /// CommonMark of the floor, headed by its floor, author and date.
+    pub fn to_markdown(&self) -> String {
+        let header = format!(
+            "### {}樓 {} ({}) {}",
+            self.floor,
+            escape(self.user.name.as_ref()),
+            escape(self.user.id.as_ref()),
+            escape(self.date.raw.as_ref()),
+        );
+
+        let body = blocks_to_markdown(&self.desc);
+        if body.is_empty() {
+            header
+        } else {
+            format!("{}\n\n{}", header, body)
+        }
+    }
+}
+
+impl Post {
+    /// CommonMark of every fetched floor, separated by rules.
+    pub fn to_markdown(&self) -> String {
+        let floors = self
+            .posts
+            .iter()
+            .map(PostContent::to_markdown)
+            .collect::<Vec<String>>()
+            .join("\n\n---\n\n");
+
+        format!("# {}\n\n{}\n", escape(self.title.as_ref()), floors)
+    }
+}
+
+pub fn blocks_to_markdown(blocks: &[Block]) -> String {
+    blocks
+        .iter()
+        .map(block_to_markdown)
+        .filter(|md| !md.is_empty())
+        .collect::<Vec<String>>()
+        .join("\n\n")
+}
+
+fn block_to_markdown(block: &Block) -> String {
+    match block {
+        Block::Paragraph(inlines) => {
+            // a hard break can't end a paragraph
+            let md = inlines_to_markdown(inlines);
+            let mut md = md.as_str();
+            while let Some(rest) = md.trim_end_matches(' ').strip_suffix("\\\n") {
+                md = rest;
+            }
+
+            md.trim()
+                .split('\n')
+                .map(escape_block_start)
+                .collect::<Vec<String>>()
+                .join("\n")
+        }
+        Block::Image(img) => format!(
+            "![{}]({})",
+            escape(img.alt.as_ref()),
+            destination(img.original.as_ref())
+        ),
+        Block::Video(url) => format!("[{}]({})", escape(url.as_ref()), destination(url)),
+        Block::Quote(blocks) => blocks_to_markdown(blocks)
+            .lines()
+            .map(|line| format!("> {}", line).trim_end().to_string())
+            .collect::<Vec<String>>()
+            .join("\n"),
+        Block::Spoiler(blocks) => format!(
+            "<details>\n<summary>防雷</summary>\n\n{}\n\n</details>",
+            blocks_to_markdown(blocks)
+        ),
+        Block::Rule => "---".to_string(),
+    }
+}
+
+fn inlines_to_markdown(inlines: &[Inline]) -> String {
+    inlines
+        .iter()
+        .map(|inline| match inline {
+            Inline::Text(text, style) => styled(escape(text.as_ref()), style),
+            Inline::Link { href, children } => {
+                let text = inlines_to_markdown(children);
+                let text = match text.trim() {
+                    "" => escape(href),
+                    text => text.to_string(),
+                };
+                format!("[{}]({})", text, destination(href))
+            }
+            Inline::Break => "\\\n".to_string(),
+        })
+        .collect()
+}
+
+/// Emphasis can't start or end with a space, keep them outside of the markers.
+fn styled(text: String, style: &TextStyle) -> String {
+    let trimmed = text.trim();
+    if trimmed.is_empty() {
+        return text;
+    }
+
+    let mut md = trimmed.to_string();
+    if style.strike {
+        // `~~` is a gfm extension
+        md = format!("<del>{}</del>", md);
+    }
+    if style.underline {
+        md = format!("<u>{}</u>", md);
+    }
+    if style.italic {
+        md = format!("*{}*", md);
+    }
+    if style.bold {
+        md = format!("**{}**", md);
+    }
+
+    let start = &text[..text.len() - text.trim_start().len()];
+    let end = &text[text.trim_end().len()..];
+    format!("{}{}{}", start, md, end)
+}
+
+/// Link destination in `<>`, which may contain spaces and parentheses.
+fn destination(url: &str) -> String {
+    let url = url
+        .replace('\\', "\\\\")
+        .replace('<', "\\<")
+        .replace('>', "\\>")
+        .replace(['\n', '\r'], "");
+    format!("<{}>", url)
+}
+
+/// Keep a paragraph line from starting a list, a thematic break or a setext
+/// heading, the other block markers are escaped by [`escape`].
+fn escape_block_start(line: &str) -> String {
+    let rest = line.trim_start();
+    let indent = &line[..line.len() - rest.len()];
+
+    if rest.starts_with(['-', '+', '=']) {
+        return format!("{}\\{}", indent, rest);
+    }
+
+    // `1.` or `1)`, list numbers have at most 9 digits
+    let digits = rest.chars().take_while(char::is_ascii_digit).count();
+    if (1..=9).contains(&digits) && rest[digits..].starts_with(['.', ')']) {
+        return format!("{}{}\\{}", indent, &rest[..digits], &rest[digits..]);
+    }
+
+    line.to_string()
+}
+
+fn escape(text: &str) -> String {
+    text.chars()
+        .fold(String::with_capacity(text.len()), |mut s, c| {
+            if matches!(
+                c,
+                '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '#' | '~' | '|'
+            ) {
+                s.push('\\');
+            }
+            s.push(c);
+            s
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::post::Image;
+
+    fn text(text: &str) -> Inline {
+        Inline::Text(text.to_string(), TextStyle::default())
+    }
+
+    fn paragraph(inlines: Vec<Inline>) -> Block {
+        Block::Paragraph(inlines)
+    }
+
+    #[test]
+    fn escape_block_start_markers() {
+        let blocks = vec![
+            paragraph(vec![text("- not a list")]),
+            paragraph(vec![text("1. also not")]),
+            paragraph(vec![text("title"), Inline::Break, text("===")]),
+            paragraph(vec![text("title"), Inline::Break, text("---")]),
+            paragraph(vec![text("# not a heading > nor a quote")]),
+        ];
+
+        assert_eq!(
+            blocks_to_markdown(&blocks),
+            "\\- not a list\n\n\
+             1\\. also not\n\n\
+             title\\\n\\===\n\n\
+             title\\\n\\---\n\n\
+             \\# not a heading \\> nor a quote"
+        );
+    }
+
+    #[test]
+    fn wrap_link_destinations() {
+        let link = Inline::Link {
+            href: "http://x/a b(c)".to_string(),
+            children: vec![text("link")],
+        };
+        let bare = Inline::Link {
+            href: "http://x/<y>".to_string(),
+            children: vec![],
+        };
+        let image = Block::Image(Image {
+            src: "s.png".to_string(),
+            original: "http://x/a b.png".to_string(),
+            alt: "alt".to_string(),
+        });
+
+        assert_eq!(
+            blocks_to_markdown(&[paragraph(vec![link, text(" "), bare]), image]),
+            "[link](<http://x/a b(c)>) [http://x/\\<y\\>](<http://x/\\<y\\>>)\n\n\
+             ![alt](<http://x/a b.png>)"
+        );
+    }
+
+    #[test]
+    fn strike_as_html() {
+        let style = TextStyle {
+            strike: true,
+            bold: true,
+            ..TextStyle::default()
+        };
+        let blocks = [paragraph(vec![Inline::Text(" gone ".to_string(), style)])];
+
+        assert_eq!(blocks_to_markdown(&blocks), "**<del>gone</del>**");
+    }
+
+    #[test]
+    fn strip_trailing_breaks_only() {
+        let blocks = [
+            paragraph(vec![text("path C:\\"), Inline::Break, Inline::Break]),
+            paragraph(vec![text("end \\")]),
+        ];
+
+        assert_eq!(blocks_to_markdown(&blocks), "path C:\\\\\n\nend \\\\");
+    }
+}
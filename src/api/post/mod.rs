@@ -1,13 +1,21 @@
 mod comment;
 mod content;
+mod desc;
+mod markdown;
 mod page;
+mod reply;
+mod vote;
+mod write;
 
 #[allow(clippy::module_inception)]
 mod post;
 
-pub type PostDescription = Vec<String>;
+pub type PostDescription = Vec<Block>;
 
-pub use comment::PostComment;
+pub use comment::{CommentState, PostComment, PostCommentBatch};
 pub use content::{CommentReadable, PostContent};
-pub use page::{PostPage, PostPageRef, PostPageUrlParameter};
+pub use desc::{Block, Image, Inline, TextStyle};
+pub use markdown::blocks_to_markdown;
+pub use page::{FloorPosition, PostPage, PostPageRef, PostPageUrlParameter, FLOORS_PER_PAGE};
 pub use post::Post;
+pub use vote::{Vote, VoteResult};
@@ -1,11 +1,34 @@
 use std::collections::HashMap;
 
-use scraper::{ElementRef, Html, Selector};
+use async_trait::async_trait;
+use scraper::{ElementRef, Html};
 use url::Url;
 
-use crate::api::{CachedPage, DN};
+use crate::api::{parse::Field, BahamutClient, CachedPage, Error, ParseError};
 
-use super::post::Post;
+use super::{content::PostContent, post::Post};
+
+/// Floors shown on each page of a thread
+pub const FLOORS_PER_PAGE: u16 = 20;
+
+/// Where a floor is shown in a thread.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FloorPosition {
+    pub page: u16,
+    /// Index in [`Post::posts`], deleted floors may shift it
+    pub index: usize,
+}
+
+impl FloorPosition {
+    /// Position of `floor`, starting from 1.
+    pub fn of(floor: u16) -> Option<FloorPosition> {
+        let n = floor.checked_sub(1)?;
+        Some(FloorPosition {
+            page: n / FLOORS_PER_PAGE + 1,
+            index: (n % FLOORS_PER_PAGE) as usize,
+        })
+    }
+}
 
 #[derive(Default)]
 pub struct PostPageUrlParameter {
@@ -15,17 +38,15 @@ pub struct PostPageUrlParameter {
 }
 
 impl TryFrom<String> for PostPageUrlParameter {
-    type Error = &'static str;
+    type Error = Error;
     fn try_from(value: String) -> Result<Self, Self::Error> {
-        let url = Url::parse(value.as_str())
-            .map_err(|_| "invalid url string")
-            .unwrap();
-        PostPageUrlParameter::try_from(url).map_err(|_| "")
+        let url = Url::parse(value.as_str())?;
+        PostPageUrlParameter::try_from(url)
     }
 }
 
 impl TryFrom<Url> for PostPageUrlParameter {
-    type Error = &'static str;
+    type Error = Error;
     fn try_from(url: Url) -> Result<Self, Self::Error> {
         let mut ppup = PostPageUrlParameter::default();
         url.query_pairs().for_each(|(k, v)| {
@@ -53,61 +74,85 @@ pub struct PostPage {
     pub max: u16,
     pub floor: u16,
 
-    cache: HashMap<u16, Option<Post>>,
-    first_page_html: Option<Html>,
+    client: BahamutClient,
+    cache: HashMap<u16, Post>,
+    first_page_html: Option<String>,
 }
 
 impl PostPage {
-    pub fn new(board_id: &str, id: &str) -> PostPage {
+    pub fn new(client: &BahamutClient, board_id: &str, id: &str) -> PostPage {
         PostPage {
             board_id: board_id.to_string(),
             id: id.to_string(),
             page: 1,
             max: 0,
             floor: 0,
+            client: client.clone(),
             cache: HashMap::new(),
             first_page_html: None,
         }
     }
 
-    pub fn init(&mut self) {
-        if let Some(document) = self.get_page_html(1) {
-            let root = document.root_element();
-            let max = PostPage::try_page_from_html(&root).map_or(0, |v| v);
-            self.max = max;
-            self.first_page_html = Some(document);
+    pub fn floor(&mut self, floor: u16) {
+        self.floor = floor;
+    }
+
+    /// Page and index of `floor`, [`Error::NotFound`] past the last floor
+    /// or page when they're known.
+    pub fn locate_floor(&self, floor: u16) -> Result<FloorPosition, Error> {
+        let pos = FloorPosition::of(floor).ok_or(Error::NotFound)?;
+        let over_floor = self.floor != 0 && floor > self.floor;
+        let over_page = self.max != 0 && pos.page > self.max;
+
+        if over_floor || over_page {
+            Err(Error::NotFound)
+        } else {
+            Ok(pos)
         }
     }
 
-    pub fn floor(&mut self, floor: u16) {
-        self.floor = floor;
+    /// Fetch the page of `floor` and return it, the page is cached.
+    pub async fn get_floor(&mut self, floor: u16) -> Result<PostContent, Error> {
+        let pos = self.locate_floor(floor)?;
+        let post = self.get_and_cache(pos.page, false).await?;
+        post.get_floor(floor).cloned().ok_or(Error::NotFound)
     }
 
-    fn try_page_from_html(document: &ElementRef) -> Option<u16> {
-        let selector = Selector::parse(".BH-pagebtnA a").unwrap();
-        let max: u16 = document
-            .select(&selector)
-            .last()?
-            .text()
-            .next()?
-            .to_string()
-            .parse()
-            .unwrap();
+    fn try_page_from_html(document: &ElementRef) -> Result<u16, ParseError> {
+        let field = Field::new("PostPage::max", ".BH-pagebtnA a");
 
-        Some(max)
+        // posts with a single page don't have the page buttons
+        match field.all(document)?.last() {
+            Some(last) => field.number(&last.text().collect::<String>()),
+            None => Ok(1),
+        }
     }
 }
 
+#[async_trait]
 impl CachedPage<Post> for PostPage {
-    fn cache(&self) -> &HashMap<u16, Option<Post>> {
+    fn client(&self) -> &BahamutClient {
+        &self.client
+    }
+
+    fn cache(&self) -> &HashMap<u16, Post> {
         &self.cache
     }
 
-    fn insert_cache(&mut self, page: &u16, obj: Option<Post>) {
+    fn insert_cache(&mut self, page: &u16, obj: Post) {
         self.cache.insert(*page, obj);
     }
 
-    fn cached_page_html(&self, page: u16) -> Option<Html> {
+    async fn init(&mut self) -> Result<(), Error> {
+        let html = self.get_page_html(1).await?;
+        let document = Html::parse_document(html.as_ref());
+        let max = PostPage::try_page_from_html(&document.root_element()).unwrap_or(0);
+        self.max = max;
+        self.first_page_html = Some(html);
+        Ok(())
+    }
+
+    fn cached_page_html(&self, page: u16) -> Option<String> {
         if page == 1 {
             self.first_page_html.clone()
         } else {
@@ -117,10 +162,10 @@ impl CachedPage<Post> for PostPage {
 
     fn url(&self, page: &u16) -> Url {
         let url = format!(
-            "{}C.php?bsn={}&snA={}&page={}&tnum={}",
-            DN, self.board_id, self.id, page, self.floor
+            "C.php?bsn={}&snA={}&page={}&tnum={}",
+            self.board_id, self.id, page, self.floor
         );
-        Url::parse(url.as_ref()).unwrap()
+        self.client.url(url.as_ref())
     }
 
     fn page(&self) -> u16 {
@@ -140,16 +185,18 @@ impl CachedPage<Post> for PostPage {
     }
 }
 
-impl TryFrom<PostPageUrlParameter> for PostPage {
-    type Error = &'static str;
+impl TryFrom<(&BahamutClient, PostPageUrlParameter)> for PostPage {
+    type Error = Error;
 
-    fn try_from(value: PostPageUrlParameter) -> Result<Self, Self::Error> {
+    fn try_from(
+        (client, value): (&BahamutClient, PostPageUrlParameter),
+    ) -> Result<Self, Self::Error> {
         let PostPageUrlParameter {
             board_id,
             id,
             floor,
         } = value;
-        let mut page = PostPage::new(board_id.as_ref(), id.as_ref());
+        let mut page = PostPage::new(client, board_id.as_ref(), id.as_ref());
         page.floor(floor);
         Ok(page)
     }
@@ -164,7 +211,7 @@ pub struct PostPageRef {
 }
 
 impl TryFrom<&PostPage> for PostPageRef {
-    type Error = &'static str;
+    type Error = Error;
     fn try_from(value: &PostPage) -> Result<Self, Self::Error> {
         Ok(PostPageRef {
             board_id: value.board_id.to_owned(),
@@ -1,7 +1,7 @@
-use scraper::{ElementRef, Selector};
+use scraper::ElementRef;
 use url::Url;
 
-use crate::api::{user::User, WebSite};
+use crate::api::{is_missing_page, parse::Field, Error, ParseError, WebSite};
 
 use super::content::PostContent;
 
@@ -14,68 +14,61 @@ pub struct Post {
 }
 
 impl Post {
-    pub fn posts(document: &ElementRef) -> Vec<PostContent> {
-        let selector = Post::get_root_elm_selector();
-        document
-            .select(&selector)
-            .filter_map(|dom| {
-                Some(PostContent {
-                    id: PostContent::try_id_from_html(&dom)?,
-                    desc: PostContent::try_desc_from_html(&dom)?,
-                    user: User::try_from(&dom).ok()?,
-                    floor: PostContent::try_floor_from_html(&dom)?,
-                    date: PostContent::try_date_from_html(&dom)?,
-                })
-            })
-            .collect::<Vec<PostContent>>()
+    /// Parse a saved `C.php` page, `url` is the page url and must contain
+    /// the `snA` and `tnum` queries.
+    pub fn from_html(html: &str, url: &Url) -> Result<Post, Error> {
+        Post::try_from(WebSite::from_html(html, url))
     }
 
-    fn get_root_elm_selector() -> Selector {
-        Selector::parse(".c-section[id]").unwrap()
-    }
+    /// Floors without an id are skipped.
+    pub fn posts(document: &ElementRef) -> Vec<PostContent> {
+        let mut posts = Post::root_field()
+            .all(document)
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|dom| PostContent::try_from(dom).ok())
+            .collect::<Vec<PostContent>>();
 
-    fn try_id_from_url(url: &Url) -> Option<String> {
-        let query = url
-            .query_pairs()
-            .find(|(k, _)| k == "snA")
-            .map(|(_, v)| v)?;
+        Post::link_quoted_authors(&mut posts);
+        posts
+    }
 
-        Some(query.to_string())
+    fn root_field() -> Field {
+        Field::new("Post::posts", ".c-section[id]")
     }
 
-    fn try_last_floor_from_url(url: &Url) -> Option<u16> {
-        let query = url
-            .query_pairs()
-            .find(|(k, _)| k == "tnum")
-            .map(|(_, v)| v)?;
+    fn try_id_from_url(url: &Url) -> Result<String, ParseError> {
+        Field::new("Post::id", "snA").query(url)
+    }
 
-        Some(query.parse().unwrap())
+    fn try_last_floor_from_url(url: &Url) -> Result<u16, ParseError> {
+        let field = Field::new("Post::floor", "tnum");
+        field.number::<u16>(&field.query(url)?)
     }
 
-    fn try_title_from_html(document: &ElementRef) -> Option<String> {
-        let selector = Selector::parse(".c-post__header__title").unwrap();
-        let title = document
-            .select(&selector)
-            .next()?
-            .text()
-            .collect::<String>();
+    fn try_title_from_html(document: &ElementRef) -> Result<String, ParseError> {
+        let field = Field::new("Post::title", ".c-post__header__title");
+        let title = field.first(document)?.text().collect::<String>();
 
-        Some(title)
+        Ok(title)
     }
 }
 
 impl TryFrom<WebSite> for Post {
-    type Error = &'static str;
+    type Error = Error;
 
     fn try_from(web: WebSite) -> Result<Self, Self::Error> {
         let WebSite { url, document } = web;
-        let selector = Post::get_root_elm_selector();
-        let top_post_elm = document.select(&selector).next().unwrap();
+        let top_post_elm = match Post::root_field().first(&document.root_element()) {
+            Ok(elm) => elm,
+            Err(_) if is_missing_page(&document) => return Err(Error::NotFound),
+            Err(e) => return Err(e.into()),
+        };
 
         let post = Post {
-            id: Post::try_id_from_url(&url).ok_or("can't get id")?,
-            floor: Post::try_last_floor_from_url(&url).ok_or("can't get last floor")?,
-            title: Post::try_title_from_html(&top_post_elm).ok_or("post title invalid")?,
+            id: Post::try_id_from_url(&url)?,
+            floor: Post::try_last_floor_from_url(&url)?,
+            title: Post::try_title_from_html(&top_post_elm)?,
             posts: Post::posts(&document.root_element()),
         };
 
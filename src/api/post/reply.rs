@@ -0,0 +1,183 @@
+use std::collections::BTreeMap;
+
+use super::{content::PostContent, post::Post, Block, Inline};
+
+impl PostContent {
+    /// Floors referenced in the content as `#12`, `B12` or `12樓`.
+    ///
+    /// Only floors before this one are kept, a reply can't quote the future.
+    pub fn try_replies_from_desc(desc: &[Block], floor: u16) -> Vec<u16> {
+        let text = desc.iter().map(prose).collect::<Vec<String>>().join("\n");
+        let mut replies = floor_refs(text.as_ref())
+            .into_iter()
+            .filter(|f| *f > 0 && (floor == 0 || *f < floor))
+            .collect::<Vec<u16>>();
+
+        replies.sort_unstable();
+        replies.dedup();
+        replies
+    }
+}
+
+impl Post {
+    /// Quotes without a floor number, resolved by the name or id of the
+    /// quoted author. Only floors of the same page can be found.
+    pub(super) fn link_quoted_authors(posts: &mut [PostContent]) {
+        for i in 0..posts.len() {
+            let (before, rest) = posts.split_at_mut(i);
+            let current = &mut rest[0];
+
+            let quoted = current
+                .desc
+                .iter()
+                .filter_map(|block| match block {
+                    Block::Quote(blocks) => Some(blocks.iter().map(prose).collect::<String>()),
+                    _ => None,
+                })
+                .filter(|text| floor_refs(text.as_ref()).is_empty())
+                .filter_map(|text| {
+                    // the latest floor of the quoted author
+                    before
+                        .iter()
+                        .rev()
+                        .find(|p| {
+                            let user = &p.user;
+                            (!user.id.is_empty() && text.contains(user.id.as_str()))
+                                || (!user.name.is_empty() && text.contains(user.name.as_str()))
+                        })
+                        .map(|p| p.floor)
+                })
+                .collect::<Vec<u16>>();
+
+            if !quoted.is_empty() {
+                current.replies_to.extend(quoted);
+                current.replies_to.sort_unstable();
+                current.replies_to.dedup();
+            }
+        }
+    }
+
+    /// Floors replying to each floor, keyed by the replied floor.
+    pub fn replied_by(&self) -> BTreeMap<u16, Vec<u16>> {
+        self.posts.iter().fold(BTreeMap::new(), |mut map, post| {
+            post.replies_to.iter().for_each(|floor| {
+                map.entry(*floor).or_insert_with(Vec::new).push(post.floor);
+            });
+            map
+        })
+    }
+
+    /// Floors replying to `floor`.
+    pub fn replies_of(&self, floor: u16) -> Vec<u16> {
+        self.posts
+            .iter()
+            .filter(|post| post.replies_to.contains(&floor))
+            .map(|post| post.floor)
+            .collect()
+    }
+
+    pub fn get_floor(&self, floor: u16) -> Option<&PostContent> {
+        self.posts.iter().find(|post| post.floor == floor)
+    }
+}
+
+/// Text written in paragraphs, quotes and spoilers. Image and video urls and
+/// links showing their url are left out, `/b12.png` isn't a floor.
+fn prose(block: &Block) -> String {
+    match block {
+        Block::Paragraph(inlines) => inlines
+            .iter()
+            .map(|inline| match inline {
+                Inline::Link { .. } if inline.text().contains("://") => String::new(),
+                inline => inline.text(),
+            })
+            .collect(),
+        Block::Quote(blocks) | Block::Spoiler(blocks) => {
+            blocks.iter().map(prose).collect::<Vec<String>>().join("\n")
+        }
+        Block::Image(_) | Block::Video(_) | Block::Rule => String::new(),
+    }
+}
+
+/// Numbers written as `#12`, `B12`, `b12` or `12樓`.
+fn floor_refs(text: &str) -> Vec<u16> {
+    let chars = text.chars().collect::<Vec<char>>();
+    let mut refs = vec![];
+    let mut i = 0;
+
+    while i < chars.len() {
+        if !chars[i].is_ascii_digit() {
+            i += 1;
+            continue;
+        }
+
+        let start = i;
+        while i < chars.len() && chars[i].is_ascii_digit() {
+            i += 1;
+        }
+
+        let prev = start.checked_sub(1).map(|p| chars[p]);
+        let before_prev = start.checked_sub(2).map(|p| chars[p]);
+        let next = chars.get(i).copied();
+
+        // `B12` but not `AB12`
+        let by_prefix = match prev {
+            Some('#') | Some('＃') => true,
+            Some('B') | Some('b') => !before_prev.is_some_and(|c| c.is_ascii_alphanumeric()),
+            _ => false,
+        };
+        let by_suffix = next == Some('樓');
+        let is_word_end = !next.is_some_and(|c| c.is_ascii_alphanumeric());
+
+        if (by_prefix && is_word_end) || by_suffix {
+            let number = chars[start..i].iter().collect::<String>();
+            if let Ok(floor) = number.parse::<u16>() {
+                refs.push(floor);
+            }
+        }
+    }
+
+    refs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::post::{Image, TextStyle};
+
+    fn paragraph(text: &str) -> Block {
+        Block::Paragraph(vec![Inline::Text(text.to_string(), TextStyle::default())])
+    }
+
+    #[test]
+    fn floor_refs_forms() {
+        assert_eq!(floor_refs("#12 B3 b4 5樓 ＃6"), vec![12, 3, 4, 5, 6]);
+        assert_eq!(floor_refs("AB12 #12a B1x 2023 99999樓"), Vec::<u16>::new());
+    }
+
+    #[test]
+    fn replies_skip_urls() {
+        let desc = vec![
+            paragraph("回 #2"),
+            Block::Image(Image {
+                src: "https://i.example/b3.png".to_string(),
+                original: "https://i.example/b3.png#4".to_string(),
+                alt: String::new(),
+            }),
+            Block::Video("https://v.example/watch#5".to_string()),
+            Block::Paragraph(vec![Inline::Link {
+                href: "https://x.example/b6".to_string(),
+                children: vec![Inline::Text(
+                    "https://x.example/b6".to_string(),
+                    TextStyle::default(),
+                )],
+            }]),
+            Block::Quote(vec![paragraph("B7 說")]),
+            Block::Spoiler(vec![paragraph("8樓")]),
+        ];
+
+        assert_eq!(PostContent::try_replies_from_desc(&desc, 10), vec![2, 7, 8]);
+        // not after the floor itself
+        assert_eq!(PostContent::try_replies_from_desc(&desc, 7), vec![2]);
+    }
+}
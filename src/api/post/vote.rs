@@ -0,0 +1,87 @@
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::api::{write::submit, BahamutClient, Error};
+
+use super::{comment::PostComment, content::PostContent};
+
+static VOTE_FLOOR_PATH: &str = "ajax/gpbp.php";
+static VOTE_COMMENT_PATH: &str = "ajax/commentGpbp.php";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub enum Vote {
+    Gp,
+    Bp,
+}
+
+impl Vote {
+    /// Value of the `do` form field.
+    fn code(&self) -> &'static str {
+        match self {
+            Vote::Gp => "gp",
+            Vote::Bp => "bp",
+        }
+    }
+}
+
+/// Accepted vote, a refused one is an [`Error::FloodControl`],
+/// [`Error::PermissionDenied`] or [`Error::Rejected`] with the reason.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct VoteResult {
+    pub vote: Vote,
+    /// Count after the vote, `None` if the answer doesn't have it
+    pub count: Option<u32>,
+}
+
+impl VoteResult {
+    /// The count is `count`, or named after the vote as `gp` or `bp`.
+    fn from_json(vote: Vote, res: &Value) -> VoteResult {
+        let count = ["count", vote.code()]
+            .iter()
+            .find_map(|key| match res.get(*key)? {
+                Value::Number(n) => n.as_u64().and_then(|n| u32::try_from(n).ok()),
+                Value::String(s) => s.trim().parse::<u32>().ok(),
+                _ => None,
+            });
+
+        VoteResult { vote, count }
+    }
+
+    /// Apply the vote to local counts, `count` is used when known.
+    pub fn apply(&self, gp: &mut u32, bp: &mut u32) {
+        let target = match self.vote {
+            Vote::Gp => gp,
+            Vote::Bp => bp,
+        };
+        *target = self.count.unwrap_or(target.saturating_add(1));
+    }
+}
+
+impl PostContent {
+    /// Vote on floor `c_id` of board `id`, the same ids as
+    /// [`PostComment::get_comment`].
+    pub async fn vote(
+        client: &BahamutClient,
+        id: &str,
+        c_id: &str,
+        vote: Vote,
+    ) -> Result<VoteResult, Error> {
+        let url = client.url(VOTE_FLOOR_PATH);
+        let form = [("do", vote.code()), ("bsn", id), ("snB", c_id)];
+        let res = submit(client, &url, &form).await?;
+        Ok(VoteResult::from_json(vote, &res))
+    }
+}
+
+impl PostComment {
+    pub async fn vote(&self, client: &BahamutClient, vote: Vote) -> Result<VoteResult, Error> {
+        let url = client.url(VOTE_COMMENT_PATH);
+        let form = [
+            ("do", vote.code()),
+            ("bsn", self.bsn.as_ref()),
+            ("snC", self.sn.as_ref()),
+        ];
+        let res = submit(client, &url, &form).await?;
+        Ok(VoteResult::from_json(vote, &res))
+    }
+}
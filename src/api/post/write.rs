@@ -0,0 +1,63 @@
+use crate::api::{write::submit, BahamutClient, CachedPage, Error};
+
+use super::{comment::PostComment, page::PostPage};
+
+static SEND_COMMENT_PATH: &str = "ajax/doComment.php";
+static EDIT_COMMENT_PATH: &str = "ajax/editComment.php";
+static DELETE_COMMENT_PATH: &str = "ajax/deleteComment.php";
+static REPLY_PATH: &str = "ajax/doReply.php";
+
+impl PostComment {
+    /// Comment on floor `c_id` of board `id`, the same ids as
+    /// [`PostComment::get_comment`].
+    pub async fn send_comment(
+        client: &BahamutClient,
+        id: &str,
+        c_id: &str,
+        content: &str,
+    ) -> Result<(), Error> {
+        let url = client.url(SEND_COMMENT_PATH);
+        let form = [("bsn", id), ("snB", c_id), ("content", content)];
+        submit(client, &url, &form).await?;
+        Ok(())
+    }
+
+    /// Replace the content of this comment, only the author can edit it.
+    pub async fn edit(&self, client: &BahamutClient, content: &str) -> Result<(), Error> {
+        let url = client.url(EDIT_COMMENT_PATH);
+        let form = [
+            ("bsn", self.bsn.as_ref()),
+            ("snC", self.sn.as_ref()),
+            ("content", content),
+        ];
+        submit(client, &url, &form).await?;
+        Ok(())
+    }
+
+    /// Delete this comment, only the author or a moderator can delete it.
+    pub async fn delete(&self, client: &BahamutClient) -> Result<(), Error> {
+        let url = client.url(DELETE_COMMENT_PATH);
+        let form = [("bsn", self.bsn.as_ref()), ("snC", self.sn.as_ref())];
+        submit(client, &url, &form).await?;
+        Ok(())
+    }
+}
+
+impl PostPage {
+    /// Reply to the thread as a new floor.
+    ///
+    /// Nothing is fetched again, the pages cached by this `PostPage` and
+    /// the transport don't have the new floor. Refresh the last page with
+    /// [`CachedPage::get_and_cache`] ignoring the cache to see it.
+    pub async fn reply(&self, content: &str) -> Result<(), Error> {
+        let client = self.client();
+        let url = client.url(REPLY_PATH);
+        let form = [
+            ("bsn", self.board_id.as_ref()),
+            ("snA", self.id.as_ref()),
+            ("content", content),
+        ];
+        submit(client, &url, &form).await?;
+        Ok(())
+    }
+}
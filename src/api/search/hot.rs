@@ -0,0 +1,155 @@
+use url::Url;
+
+use crate::api::{
+    board::BoardPost,
+    get_document,
+    link::{boards_from_html, posts_from_html, section, BOARD_LINKS, POST_LINKS},
+    parse::Field,
+    BahamutClient, Error, ParseError, WebSite,
+};
+
+use super::SearchResult;
+
+/// Ranking of popular boards, or the list titled by [`HOT_BOARDS_TITLES`]
+const HOT_BOARDS_FIELD: Field = Field::new(
+    "ForumHot::boards",
+    "#hot-board, .hot-board, .popular-board, [class*=\"hotboard\"]",
+);
+const HOT_BOARDS_TITLES: [&str; 2] = ["哈啦區熱門看板", "熱門看板"];
+
+/// Ranking of hot threads, or the list titled by [`HOT_POSTS_TITLES`]
+const HOT_POSTS_FIELD: Field = Field::new(
+    "ForumHot::posts",
+    "#hot-post, .hot-post, .hot-article, [class*=\"hotpost\"]",
+);
+const HOT_POSTS_TITLES: [&str; 3] = ["熱門文章", "熱門話題", "今日熱門"];
+
+/// Rankings of the forum front page, popular boards (哈啦區熱門看板) and
+/// the hot threads of the day.
+#[derive(Clone, Default)]
+pub struct ForumHot {
+    /// In the order of the ranking, most popular first
+    pub boards: Vec<SearchResult>,
+    /// Only the id, title, url and board of each thread are known,
+    /// the board id is in [`BoardCategoryId::id`](crate::api::board::BoardCategoryId::id)
+    pub posts: Vec<BoardPost>,
+
+    /// Rankings which can't be read and were left empty
+    pub parse_errors: Vec<ParseError>,
+}
+
+impl ForumHot {
+    pub async fn get_hot(client: &BahamutClient) -> Result<ForumHot, Error> {
+        let url = client.base_url().clone();
+        let document = get_document(client, &url).await?;
+        ForumHot::try_from(WebSite { url, document })
+    }
+
+    /// Parse a saved front page, `url` is used to resolve the links.
+    pub fn from_html(html: &str, url: &Url) -> Result<ForumHot, Error> {
+        ForumHot::try_from(WebSite::from_html(html, url))
+    }
+}
+
+impl TryFrom<WebSite> for ForumHot {
+    type Error = Error;
+
+    /// Each ranking is read on its own, the page is only rejected when
+    /// neither is found.
+    fn try_from(web: WebSite) -> Result<Self, Error> {
+        let WebSite { document, url } = web;
+        let mut hot = ForumHot::default();
+
+        let boards = section(
+            &document,
+            HOT_BOARDS_FIELD,
+            &HOT_BOARDS_TITLES,
+            Field::new("ForumHot::boards", BOARD_LINKS),
+        )
+        .and_then(|boards| boards_from_html(&boards, &url, "ForumHot::boards"));
+        match boards {
+            Ok(boards) => hot.boards = boards,
+            Err(e) => hot.parse_errors.push(e),
+        };
+
+        let posts = section(
+            &document,
+            HOT_POSTS_FIELD,
+            &HOT_POSTS_TITLES,
+            Field::new("ForumHot::posts", POST_LINKS),
+        )
+        .and_then(|posts| posts_from_html(&posts, &url, "ForumHot::posts"));
+        match posts {
+            Ok(posts) => hot.posts = posts,
+            Err(e) => hot.parse_errors.push(e),
+        };
+
+        match hot.parse_errors.first() {
+            Some(e) if hot.parse_errors.len() == 2 => Err(e.clone().into()),
+            _ => Ok(hot),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NAV: &str = r#"<div class="BH-menu"><a href="B.php?bsn=1">nav board</a>
+<a href="C.php?bsn=1&amp;snA=1">nav thread</a></div>"#;
+
+    const HOT_BOARDS: &str = r#"<div class="popular-board"><h3>哈啦區熱門看板</h3><ol>
+<li><a href="B.php?bsn=60076">場外休憩區</a></li>
+<li><a href="B.php?bsn=36730">原神</a></li></ol></div>"#;
+
+    const HOT_POSTS: &str = r#"<section><h3>今日熱門</h3><ul>
+<li><a href="C.php?bsn=60076&amp;snA=123">熱門文章一</a></li>
+<li><a href="C.php?bsn=36730&amp;snA=456">熱門文章二</a></li></ul></section>"#;
+
+    fn front_page(lists: &[&str]) -> Result<ForumHot, Error> {
+        let html = format!(
+            "<html><body>{}{}<footer><a href=\"B.php?bsn=2\">foot</a></footer></body></html>",
+            NAV,
+            lists.concat()
+        );
+        let url = Url::parse("https://forum.gamer.com.tw/").unwrap();
+        ForumHot::from_html(html.as_ref(), &url)
+    }
+
+    #[test]
+    fn hot_from_front_page() {
+        let hot = front_page(&[HOT_BOARDS, HOT_POSTS]).unwrap();
+
+        assert_eq!(
+            hot.boards
+                .iter()
+                .map(|b| b.id.as_ref())
+                .collect::<Vec<&str>>(),
+            vec!["60076", "36730"]
+        );
+        assert_eq!(
+            hot.posts
+                .iter()
+                .map(|p| p.title.as_ref())
+                .collect::<Vec<&str>>(),
+            vec!["熱門文章一", "熱門文章二"]
+        );
+        assert!(hot.parse_errors.is_empty());
+    }
+
+    #[test]
+    fn hot_keeps_the_found_ranking() {
+        let hot = front_page(&[HOT_BOARDS]).unwrap();
+
+        assert_eq!(hot.boards.len(), 2);
+        assert!(hot.posts.is_empty());
+        assert_eq!(
+            hot.parse_errors
+                .iter()
+                .map(|e| e.field)
+                .collect::<Vec<&str>>(),
+            vec!["ForumHot::posts"]
+        );
+        assert!(front_page(&[]).is_err());
+    }
+}
@@ -1,36 +1,53 @@
-use futures::executor::block_on;
-use scraper::{ElementRef, Selector};
+use scraper::{ElementRef, Html};
 use serde::Serialize;
 
 use crate::api::get_document;
 
-use super::{UrlWithId, DN};
+use super::{parse::Field, BahamutClient, Error, ParseErrorKind, UrlWithId};
+
+mod hot;
+
+pub use hot::ForumHot;
 
 #[derive(Clone, Default, Serialize)]
 pub struct BoardSearch;
 impl UrlWithId<&str> for BoardSearch {
-    fn url(query: &str) -> url::Url {
-        url::Url::parse(format!("{}searchb.php?qt=board&search={}", DN, query).as_ref()).unwrap()
+    fn url(base: &url::Url, query: &str) -> url::Url {
+        base.join(format!("searchb.php?qt=board&search={}", query).as_ref())
+            .unwrap()
     }
 }
 
 impl BoardSearch {
-    pub fn get_search_result(query: &str) -> Vec<SearchResult> {
-        let url = BoardSearch::url(query);
-        let selector = Selector::parse(".BH-table tr").unwrap();
-        let td_selector = Selector::parse("td").unwrap();
-
-        if let Ok(document) = block_on(get_document(&url)) {
-            document
-                .select(&selector)
-                .filter_map(|dom| {
-                    let td = dom.select(&td_selector).nth(2)?;
-                    SearchResult::try_from(td).ok()
-                })
-                .collect::<Vec<SearchResult>>()
-        } else {
-            vec![]
-        }
+    pub async fn get_search_result(
+        client: &BahamutClient,
+        query: &str,
+    ) -> Result<Vec<SearchResult>, Error> {
+        let url = BoardSearch::url(client.base_url(), query);
+        let document = get_document(client, &url).await?;
+
+        BoardSearch::try_results_from_html(&document)
+    }
+
+    /// Parse a saved `searchb.php` page.
+    pub fn parse_results(html: &str) -> Result<Vec<SearchResult>, Error> {
+        BoardSearch::try_results_from_html(&Html::parse_document(html))
+    }
+
+    fn try_results_from_html(document: &Html) -> Result<Vec<SearchResult>, Error> {
+        let rows = Field::new("BoardSearch::result", ".BH-table tr");
+        let td_selector = Field::new("BoardSearch::result", "td").selector()?;
+
+        let result = rows
+            .all(&document.root_element())?
+            .into_iter()
+            .filter_map(|dom| {
+                let td = dom.select(&td_selector).nth(2)?;
+                SearchResult::try_from(td).ok()
+            })
+            .collect::<Vec<SearchResult>>();
+
+        Ok(result)
     }
 }
 
@@ -43,24 +60,22 @@ pub struct SearchResult {
 
 impl SearchResult {
     fn try_id_from_html(document: &ElementRef) -> Option<String> {
-        let selector = Selector::parse("a").unwrap();
-        let id = document
-            .select(&selector)
-            .next()?
+        let id = Field::new("SearchResult::id", "a")
+            .first(document)
+            .ok()?
             .value()
             .attr("href")?
             .split('=')
-            .last()?
+            .next_back()?
             .to_string();
 
         Some(id)
     }
 
     fn try_name_from_html(document: &ElementRef) -> Option<String> {
-        let selector = Selector::parse("a").unwrap();
-        let name = document
-            .select(&selector)
-            .next()?
+        let name = Field::new("SearchResult::name", "a")
+            .first(document)
+            .ok()?
             .text()
             .skip(1)
             .collect::<String>();
@@ -69,10 +84,9 @@ impl SearchResult {
     }
 
     fn try_platform_from_html(document: &ElementRef) -> Option<String> {
-        let selector = Selector::parse("a").unwrap();
-        let platform = document
-            .select(&selector)
-            .next()?
+        let platform = Field::new("SearchResult::platform", "a")
+            .first(document)
+            .ok()?
             .text()
             .next()?
             .to_string();
@@ -82,19 +96,20 @@ impl SearchResult {
 }
 
 impl TryFrom<ElementRef<'_>> for SearchResult {
-    type Error = &'static str;
+    type Error = Error;
 
     fn try_from(document: ElementRef) -> Result<Self, Self::Error> {
         let id = if let Some(id) = SearchResult::try_id_from_html(&document) {
             id
         } else {
-            return Err("");
+            let field = Field::new("SearchResult::id", "a");
+            return Err(field.error(ParseErrorKind::Attribute("href")).into());
         };
 
         let sr = SearchResult {
             id,
-            name: SearchResult::try_name_from_html(&document).unwrap(),
-            platform: SearchResult::try_platform_from_html(&document).unwrap(),
+            name: SearchResult::try_name_from_html(&document).unwrap_or_default(),
+            platform: SearchResult::try_platform_from_html(&document).unwrap_or_default(),
         };
 
         Ok(sr)
@@ -0,0 +1,313 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::Write,
+    path::Path,
+    sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard},
+};
+
+use chrono::{DateTime, Utc};
+use reqwest::{cookie::CookieStore, header::HeaderValue};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use url::Url;
+
+use super::Error;
+
+/// Login token cookie
+pub static COOKIE_TOKEN: &str = "BAHARUNE";
+/// Logged in user id cookie
+pub static COOKIE_USER_ID: &str = "BAHAID";
+/// Cookies are only sent to and accepted from this domain and its subdomains
+pub static COOKIE_DOMAIN: &str = "gamer.com.tw";
+
+/// Cookie jar shared by every request of a [`BahamutClient`](super::BahamutClient).
+///
+/// Log in with [`BahamutClient::login`](super::BahamutClient::login) or load
+/// browser cookies with [`Session::from_cookies_txt`], then keep the state
+/// across runs with [`Session::save`] and [`Session::load`].
+///
+/// Cloning is cheap, all clones share the same jar. Cookies are only sent
+/// to the hosts of [`COOKIE_DOMAIN`], which all share them, and only site
+/// wide cookies (`Path=/`) are kept.
+#[derive(Clone)]
+pub struct Session {
+    domain: Arc<str>,
+    cookies: Arc<RwLock<BTreeMap<String, String>>>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SessionData {
+    #[serde(default)]
+    domain: Option<String>,
+    cookies: BTreeMap<String, String>,
+}
+
+impl Session {
+    pub fn new() -> Session {
+        Session::with_domain(COOKIE_DOMAIN)
+    }
+
+    /// Session scoped to `domain` instead of [`COOKIE_DOMAIN`], such as
+    /// `localhost` for a local stand-in server.
+    pub fn with_domain(domain: &str) -> Session {
+        Session {
+            domain: Arc::from(domain.trim_start_matches('.')),
+            cookies: Arc::default(),
+        }
+    }
+
+    pub fn domain(&self) -> &str {
+        &self.domain
+    }
+
+    /// Read a Netscape `cookies.txt` export, only `gamer.com.tw` cookies are kept.
+    pub fn from_cookies_txt<P: AsRef<Path>>(path: P) -> Result<Session, Error> {
+        let text = fs::read_to_string(path)?;
+        Ok(Session::parse_cookies_txt(text.as_ref()))
+    }
+
+    pub fn parse_cookies_txt(text: &str) -> Session {
+        let session = Session::new();
+
+        text.lines()
+            .map(|line| line.trim_start_matches("#HttpOnly_"))
+            .filter(|line| !line.starts_with('#'))
+            .for_each(|line| {
+                let fields = line.trim_end().split('\t').collect::<Vec<&str>>();
+                if let [domain, _, _, _, _, name, value] = fields[..] {
+                    if session.is_in_scope(domain) {
+                        session.set_cookie(name, value);
+                    }
+                }
+            });
+
+        session
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Session, Error> {
+        let json = fs::read_to_string(path)?;
+        Ok(serde_json::from_str::<Session>(json.as_ref())?)
+    }
+
+    /// Write the session readable by the owner only, it holds the login token.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        let json = serde_json::to_string(self)?;
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+
+            options.mode(0o600);
+            // the mode only applies to new files
+            if path.as_ref().exists() {
+                fs::set_permissions(&path, fs::Permissions::from_mode(0o600))?;
+            }
+        }
+
+        let mut file = options.open(path)?;
+        Ok(file.write_all(json.as_bytes())?)
+    }
+
+    pub fn cookie(&self, name: &str) -> Option<String> {
+        self.read().get(name).cloned()
+    }
+
+    pub fn cookies(&self) -> BTreeMap<String, String> {
+        self.read().clone()
+    }
+
+    pub fn set_cookie(&self, name: &str, value: &str) {
+        self.write().insert(name.to_string(), value.to_string());
+    }
+
+    pub fn remove_cookie(&self, name: &str) {
+        self.write().remove(name);
+    }
+
+    pub fn clear(&self) {
+        self.write().clear();
+    }
+
+    pub fn is_logged_in(&self) -> bool {
+        self.cookie(COOKIE_TOKEN).is_some()
+    }
+
+    pub fn user_id(&self) -> Option<String> {
+        self.cookie(COOKIE_USER_ID)
+    }
+
+    /// `host` is the session domain or one of its subdomains.
+    fn is_in_scope(&self, host: &str) -> bool {
+        let host = host.trim_start_matches('.');
+        host.eq_ignore_ascii_case(&self.domain)
+            || host
+                .to_ascii_lowercase()
+                .ends_with(format!(".{}", self.domain.to_ascii_lowercase()).as_str())
+    }
+
+    fn is_url_in_scope(&self, url: &Url) -> bool {
+        url.host_str().is_some_and(|host| self.is_in_scope(host))
+    }
+
+    fn read(&self) -> RwLockReadGuard<'_, BTreeMap<String, String>> {
+        self.cookies.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, BTreeMap<String, String>> {
+        self.cookies.write().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl CookieStore for Session {
+    fn set_cookies(&self, cookie_headers: &mut dyn Iterator<Item = &HeaderValue>, url: &Url) {
+        if !self.is_url_in_scope(url) {
+            return;
+        }
+
+        cookie_headers
+            .filter_map(|header| header.to_str().ok())
+            .for_each(|header| {
+                let mut attrs = header.split(';').map(str::trim);
+                let Some((name, value)) = attrs.next().and_then(|v| v.split_once('=')) else {
+                    return;
+                };
+
+                let mut expired = false;
+                for (key, attr) in attrs.filter_map(|attr| attr.split_once('=')) {
+                    let attr = attr.trim();
+                    match key.trim().to_ascii_lowercase().as_str() {
+                        // a cookie for another site, or for part of this one
+                        "domain" if !self.is_in_scope(attr) => return,
+                        "path" if attr != "/" => return,
+                        "max-age" => expired |= attr.parse::<i64>().is_ok_and(|v| v <= 0),
+                        "expires" => expired |= is_past_date(attr),
+                        _ => (),
+                    }
+                }
+
+                if expired || value.is_empty() || value == "deleted" {
+                    self.remove_cookie(name);
+                } else {
+                    self.set_cookie(name, value);
+                }
+            });
+    }
+
+    fn cookies(&self, url: &Url) -> Option<HeaderValue> {
+        let cookies = self.read();
+        if cookies.is_empty() || !self.is_url_in_scope(url) {
+            return None;
+        }
+
+        let header = cookies
+            .iter()
+            .map(|(k, v)| format!("{}={}", k, v))
+            .collect::<Vec<String>>()
+            .join("; ");
+
+        HeaderValue::from_str(header.as_ref()).ok()
+    }
+}
+
+/// `Expires` in the past, also in the `01-Jan-1970` form used to delete.
+fn is_past_date(date: &str) -> bool {
+    DateTime::parse_from_rfc2822(date.replace('-', " ").as_ref())
+        .is_ok_and(|date| date.with_timezone(&Utc) <= Utc::now())
+}
+
+impl Default for Session {
+    fn default() -> Self {
+        Session::new()
+    }
+}
+
+impl Serialize for Session {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SessionData {
+            domain: Some(self.domain.to_string()),
+            cookies: self.cookies(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Session {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let SessionData { domain, cookies } = SessionData::deserialize(deserializer)?;
+        let session = Session::with_domain(domain.as_deref().unwrap_or(COOKIE_DOMAIN));
+        *session.write() = cookies;
+        Ok(session)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(session: &Session, url: &str, header: &str) {
+        let header = HeaderValue::from_str(header).unwrap();
+        let url = Url::parse(url).unwrap();
+        session.set_cookies(&mut std::iter::once(&header), &url);
+    }
+
+    #[test]
+    fn set_cookies_scope() {
+        let session = Session::new();
+        let url = "https://forum.gamer.com.tw/C.php";
+
+        set(&session, url, "A=1; Domain=.gamer.com.tw; Path=/");
+        set(&session, url, "B=2; Domain=evil.example; Path=/");
+        set(&session, url, "C=3; Domain=evilgamer.com.tw");
+        set(&session, url, "D=4; Path=/ajax");
+        set(&session, "https://evil.example/", "E=5");
+
+        assert_eq!(session.cookie("A"), Some("1".to_string()));
+        assert_eq!(session.cookies().len(), 1);
+
+        let url = Url::parse("https://evil.example/").unwrap();
+        assert!(CookieStore::cookies(&session, &url).is_none());
+        let url = Url::parse("https://home.gamer.com.tw/").unwrap();
+        assert_eq!(
+            CookieStore::cookies(&session, &url),
+            HeaderValue::from_str("A=1").ok()
+        );
+    }
+
+    #[test]
+    fn set_cookies_deletes() {
+        let session = Session::new();
+        let url = "https://forum.gamer.com.tw/";
+        for name in ["A", "B", "C", "D"] {
+            session.set_cookie(name, "1");
+        }
+
+        set(&session, url, "A=1; Max-Age=0");
+        set(&session, url, "B=1; Expires=Thu, 01-Jan-1970 00:00:01 GMT");
+        set(&session, url, "C=deleted");
+        set(
+            &session,
+            url,
+            "D=2; Expires=Fri, 01 Jan 2100 00:00:00 GMT; Max-Age=60",
+        );
+
+        assert_eq!(session.cookies().into_keys().collect::<Vec<_>>(), vec!["D"]);
+        assert_eq!(session.cookie("D"), Some("2".to_string()));
+    }
+
+    #[test]
+    fn parse_cookies_txt_scope() {
+        let text = "# Netscape HTTP Cookie File\n\
+            #HttpOnly_.gamer.com.tw\tTRUE\t/\tTRUE\t0\tBAHARUNE\ttoken\n\
+            .gamer.com.tw\tTRUE\t/\tFALSE\t0\tBAHAID\tuser\n\
+            .example.com\tTRUE\t/\tFALSE\t0\tOTHER\tx\n\
+            # .gamer.com.tw\tTRUE\t/\tFALSE\t0\tCOMMENTED\tx\n\
+            broken line\n";
+        let session = Session::parse_cookies_txt(text);
+
+        assert!(session.is_logged_in());
+        assert_eq!(session.user_id(), Some("user".to_string()));
+        assert_eq!(session.cookies().len(), 2);
+    }
+}
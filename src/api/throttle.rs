@@ -0,0 +1,252 @@
+//! Request pacing of [`ReqwestTransport`](super::ReqwestTransport),
+//! a token bucket per host and retries of failed page fetches.
+
+use std::{
+    collections::{hash_map::RandomState, HashMap},
+    hash::{BuildHasher, Hasher},
+    sync::Mutex,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use chrono::{DateTime, Utc};
+use reqwest::{header::RETRY_AFTER, Response};
+use url::Url;
+
+use super::Error;
+
+/// Requests allowed per second to a host, see
+/// [`BahamutClientBuilder::rate_limit`](super::BahamutClientBuilder::rate_limit).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RateLimit {
+    per_second: f64,
+    burst: u32,
+}
+
+impl RateLimit {
+    /// `per_second` requests on average, up to `burst` of them back to back
+    /// after being idle. The rate is at least one request every 1000s.
+    pub fn new(per_second: f64, burst: u32) -> RateLimit {
+        RateLimit {
+            per_second: per_second.max(0.001),
+            burst: burst.max(1),
+        }
+    }
+
+    pub fn per_second(per_second: f64) -> RateLimit {
+        RateLimit::new(per_second, 1)
+    }
+}
+
+/// Retries of page fetches, forms are never sent twice.
+///
+/// Timeouts, network errors, 429 and 5xx answers are retried after an
+/// exponential backoff with jitter, or after the `Retry-After` of the answer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Retries after the first try
+    pub max_retries: u32,
+    /// Backoff of the first retry, doubled on every next one
+    pub base_delay: Duration,
+    /// Longest wait, a longer `Retry-After` gives up instead
+    pub max_delay: Duration,
+}
+
+impl RetryPolicy {
+    /// Fail on the first error.
+    pub fn none() -> RetryPolicy {
+        RetryPolicy {
+            max_retries: 0,
+            ..RetryPolicy::default()
+        }
+    }
+
+    pub(crate) fn is_retryable(error: &Error) -> bool {
+        match error {
+            Error::Timeout | Error::Network(_) => true,
+            Error::Status(status) => *status == 429 || *status >= 500,
+            _ => false,
+        }
+    }
+
+    /// Wait before retry `attempt` (0 based), `None` to give up.
+    pub(crate) fn delay(&self, attempt: u32, retry_after: Option<Duration>) -> Option<Duration> {
+        if attempt >= self.max_retries {
+            return None;
+        }
+
+        match retry_after {
+            Some(wait) if wait > self.max_delay => None,
+            Some(wait) => Some(wait),
+            None => {
+                let backoff = self
+                    .base_delay
+                    .saturating_mul(2u32.saturating_pow(attempt))
+                    .min(self.max_delay);
+                Some(jitter(backoff))
+            }
+        }
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_retries: 2,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(10),
+        }
+    }
+}
+
+/// Between half and all of `delay`, so clients failing together don't
+/// retry together.
+fn jitter(delay: Duration) -> Duration {
+    let mut hasher = RandomState::new().build_hasher();
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    hasher.write_u128(now.as_nanos());
+    let ratio = (hasher.finish() % 1000) as f64 / 1000.0;
+    delay.mul_f64(0.5 + ratio / 2.0)
+}
+
+/// `Retry-After` of the answer, in seconds or as a http date.
+pub(crate) fn retry_after(res: &Response) -> Option<Duration> {
+    let value = res.headers().get(RETRY_AFTER)?.to_str().ok()?.trim();
+
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    (date.with_timezone(&Utc) - Utc::now()).to_std().ok()
+}
+
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+    /// Set by a `Retry-After`, no request to the host until then
+    paused_until: Option<Instant>,
+}
+
+/// Buckets of every host, shared by the clones of a client.
+pub(crate) struct HostLimiter {
+    rate: Option<RateLimit>,
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+impl HostLimiter {
+    pub(crate) fn new(rate: Option<RateLimit>) -> HostLimiter {
+        HostLimiter {
+            rate,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn host(url: &Url) -> String {
+        format!(
+            "{}:{}",
+            url.host_str().unwrap_or_default(),
+            url.port_or_known_default().unwrap_or_default()
+        )
+    }
+
+    fn new_bucket(&self, now: Instant) -> Bucket {
+        Bucket {
+            tokens: self.rate.map_or(0.0, |rate| rate.burst as f64),
+            updated: now,
+            paused_until: None,
+        }
+    }
+
+    /// Wait for the turn of a request to the host of `url`.
+    pub(crate) async fn acquire(&self, url: &Url) {
+        let host = HostLimiter::host(url);
+
+        loop {
+            match self.try_acquire(host.as_ref()) {
+                Some(wait) => tokio::time::sleep(wait).await,
+                None => return,
+            }
+        }
+    }
+
+    /// Take a token, or how long to wait before trying again.
+    fn try_acquire(&self, host: &str) -> Option<Duration> {
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+        let bucket = buckets
+            .entry(host.to_string())
+            .or_insert_with(|| self.new_bucket(now));
+
+        if let Some(until) = bucket.paused_until {
+            if until > now {
+                return Some(until - now);
+            }
+            bucket.paused_until = None;
+        }
+
+        let rate = self.rate?;
+        let elapsed = now.duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * rate.per_second).min(rate.burst as f64);
+        bucket.updated = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            None
+        } else {
+            Some(Duration::from_secs_f64(
+                (1.0 - bucket.tokens) / rate.per_second,
+            ))
+        }
+    }
+
+    /// Hold every request to the host of `url` for `wait`.
+    pub(crate) fn pause(&self, url: &Url, wait: Duration) {
+        let until = Instant::now() + wait;
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+        let bucket = buckets
+            .entry(HostLimiter::host(url))
+            .or_insert_with(|| self.new_bucket(Instant::now()));
+
+        if bucket.paused_until.is_none_or(|paused| paused < until) {
+            bucket.paused_until = Some(until);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delay_gives_up_after_max_retries() {
+        let policy = RetryPolicy::default();
+        assert!(policy.delay(1, None).is_some());
+        assert_eq!(policy.delay(2, None), None);
+        assert_eq!(RetryPolicy::none().delay(0, None), None);
+    }
+
+    #[test]
+    fn delay_follows_retry_after() {
+        let policy = RetryPolicy::default();
+        let wait = Duration::from_secs(3);
+        assert_eq!(policy.delay(0, Some(wait)), Some(wait));
+        assert_eq!(policy.delay(0, Some(Duration::from_secs(11))), None);
+    }
+
+    #[test]
+    fn delay_backoff_with_jitter() {
+        let policy = RetryPolicy {
+            max_retries: 10,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(10),
+        };
+
+        for (attempt, backoff) in [(0, 500), (1, 1000), (3, 4000), (6, 10_000)] {
+            let backoff = Duration::from_millis(backoff);
+            let delay = policy.delay(attempt, None).unwrap();
+            assert!(delay >= backoff / 2 && delay <= backoff, "{:?}", delay);
+        }
+    }
+}
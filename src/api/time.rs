@@ -0,0 +1,305 @@
+use std::fmt::Display;
+
+use chrono::{
+    DateTime, Datelike, Duration, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc,
+};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Every time on the forum is in Asia/Taipei, which has no daylight saving.
+const TAIPEI_OFFSET: i32 = 8 * 3600;
+
+const FORMATS: [&str; 4] = [
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%d %H:%M",
+    "%Y/%m/%d %H:%M:%S",
+    "%Y/%m/%d %H:%M",
+];
+
+const DATE_FORMATS: [&str; 2] = ["%Y-%m-%d", "%Y/%m/%d"];
+
+/// Dates without a year, like `05-01 10:00` or `05/01`
+const YEARLESS_FORMATS: [&str; 4] = ["%m-%d %H:%M", "%m/%d %H:%M", "%m-%d", "%m/%d"];
+
+/// `今日 12:34`, the number of days before today
+const DAY_WORDS: [(&str, i64); 5] = [
+    ("今天", 0),
+    ("今日", 0),
+    ("昨天", 1),
+    ("昨日", 1),
+    ("前天", 2),
+];
+
+/// `3 分鐘前`
+const AGO_UNITS: [(&str, i64); 7] = [
+    ("秒", 1),
+    ("分鐘", 60),
+    ("分", 60),
+    ("小時", 3600),
+    ("天", 86400),
+    ("日", 86400),
+    ("週", 7 * 86400),
+];
+
+pub fn taipei() -> FixedOffset {
+    FixedOffset::east_opt(TAIPEI_OFFSET).expect("invalid taipei offset")
+}
+
+/// Current time in Asia/Taipei.
+pub fn now() -> DateTime<FixedOffset> {
+    Utc::now().with_timezone(&taipei())
+}
+
+/// A time shown on the forum, the raw text is kept since the site
+/// sometimes shows things we can't parse.
+///
+/// Ordering compares the parsed time first, unparsed timestamps come first.
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Timestamp {
+    pub time: Option<DateTime<FixedOffset>>,
+    pub raw: String,
+}
+
+impl Timestamp {
+    /// Parse absolute (`2023-05-01 10:00:00`), yearless (`05/01 10:00`) and
+    /// relative (`昨天 12:34`, `3 分鐘前`) times, relative to now.
+    pub fn parse(raw: &str) -> Timestamp {
+        Timestamp::parse_at(raw, now())
+    }
+
+    /// Same as [`Timestamp::parse`] with relative times counted from `now`.
+    pub fn parse_at(raw: &str, now: DateTime<FixedOffset>) -> Timestamp {
+        let raw = raw.trim();
+        let now = now.with_timezone(&taipei());
+        let time = parse_absolute(raw)
+            .or_else(|| parse_yearless(raw, now))
+            .or_else(|| parse_relative(raw, now));
+
+        Timestamp {
+            time,
+            raw: raw.to_string(),
+        }
+    }
+
+    pub fn is_parsed(&self) -> bool {
+        self.time.is_some()
+    }
+
+    /// `剛剛`, `3分鐘前`, `2小時前`, `5天前`, then the date for older times,
+    /// the raw text if it couldn't be parsed.
+    pub fn relative_to(&self, now: DateTime<FixedOffset>) -> String {
+        let Some(time) = self.time else {
+            return self.raw.to_owned();
+        };
+
+        let secs = (now - time).num_seconds();
+        match secs {
+            s if s < 0 => time.format("%Y-%m-%d %H:%M").to_string(),
+            0..=59 => "剛剛".to_string(),
+            60..=3599 => format!("{}分鐘前", secs / 60),
+            3600..=86399 => format!("{}小時前", secs / 3600),
+            86400..=604799 => format!("{}天前", secs / 86400),
+            _ => time.format("%Y-%m-%d %H:%M").to_string(),
+        }
+    }
+}
+
+fn local(time: NaiveDateTime) -> Option<DateTime<FixedOffset>> {
+    taipei().from_local_datetime(&time).single()
+}
+
+fn parse_absolute(raw: &str) -> Option<DateTime<FixedOffset>> {
+    FORMATS
+        .iter()
+        .find_map(|fmt| NaiveDateTime::parse_from_str(raw, fmt).ok())
+        .or_else(|| {
+            DATE_FORMATS
+                .iter()
+                .find_map(|fmt| NaiveDate::parse_from_str(raw, fmt).ok())
+                .map(|date| date.and_time(NaiveTime::MIN))
+        })
+        .and_then(local)
+}
+
+/// The site drops the year for recent times, a date after today
+/// belongs to last year.
+fn parse_yearless(raw: &str, now: DateTime<FixedOffset>) -> Option<DateTime<FixedOffset>> {
+    let with_year = |year: i32| {
+        let raw = format!("{} {}", year, raw);
+        YEARLESS_FORMATS.iter().find_map(|fmt| {
+            let fmt = format!("%Y {}", fmt);
+            NaiveDateTime::parse_from_str(raw.as_ref(), fmt.as_ref())
+                .ok()
+                .or_else(|| {
+                    NaiveDate::parse_from_str(raw.as_ref(), fmt.as_ref())
+                        .ok()
+                        .map(|date| date.and_time(NaiveTime::MIN))
+                })
+        })
+    };
+
+    let time = local(with_year(now.year())?)?;
+    if time > now {
+        local(with_year(now.year() - 1)?)
+    } else {
+        Some(time)
+    }
+}
+
+fn parse_relative(raw: &str, now: DateTime<FixedOffset>) -> Option<DateTime<FixedOffset>> {
+    let text = raw.split_whitespace().collect::<String>();
+
+    if text == "剛剛" {
+        return Some(now);
+    }
+
+    // 12:34 today
+    if let Ok(time) = NaiveTime::parse_from_str(text.as_ref(), "%H:%M") {
+        return local(now.date_naive().and_time(time));
+    }
+
+    // 昨天12:34
+    for (word, days) in DAY_WORDS {
+        if let Some(time) = text.strip_prefix(word) {
+            let time = NaiveTime::parse_from_str(time, "%H:%M").ok()?;
+            let date = now
+                .date_naive()
+                .checked_sub_signed(Duration::try_days(days)?)?;
+            return local(date.and_time(time));
+        }
+    }
+
+    // 3分鐘前
+    let text = text.strip_suffix('前')?;
+    AGO_UNITS.iter().find_map(|(unit, secs)| {
+        let count = text.strip_suffix(unit)?.parse::<i64>().ok()?;
+        let ago = Duration::try_seconds(count.checked_mul(*secs)?)?;
+        now.checked_sub_signed(ago)
+    })
+}
+
+impl Display for Timestamp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.raw)
+    }
+}
+
+impl From<&str> for Timestamp {
+    fn from(raw: &str) -> Self {
+        Timestamp::parse(raw)
+    }
+}
+
+/// The parsed time is kept as rfc 3339, parsing the raw text again would
+/// move relative times.
+#[derive(Serialize, Deserialize)]
+struct TimestampData {
+    time: Option<String>,
+    raw: String,
+}
+
+/// A saved [`TimestampData`], or the text of a time from the forum json.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum TimestampRepr {
+    Data(TimestampData),
+    Raw(String),
+}
+
+impl Serialize for Timestamp {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        TimestampData {
+            time: self.time.map(|time| time.to_rfc3339()),
+            raw: self.raw.to_owned(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Timestamp {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match TimestampRepr::deserialize(deserializer)? {
+            TimestampRepr::Data(TimestampData { time, raw }) => Ok(Timestamp {
+                time: time.and_then(|time| DateTime::parse_from_rfc3339(time.as_ref()).ok()),
+                raw,
+            }),
+            TimestampRepr::Raw(raw) => Ok(Timestamp::parse(raw.as_ref())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(raw: &str) -> DateTime<FixedOffset> {
+        let time = NaiveDateTime::parse_from_str(raw, "%Y-%m-%d %H:%M:%S").unwrap();
+        local(time).unwrap()
+    }
+
+    #[test]
+    fn parse_absolute_and_yearless() {
+        let now = at("2023-05-10 12:00:00");
+
+        assert_eq!(
+            Timestamp::parse_at("2023-05-01 10:00:00", now).time,
+            Some(at("2023-05-01 10:00:00"))
+        );
+        assert_eq!(
+            Timestamp::parse_at("2023/05/01", now).time,
+            Some(at("2023-05-01 00:00:00"))
+        );
+        assert_eq!(
+            Timestamp::parse_at("05/01 10:00", now).time,
+            Some(at("2023-05-01 10:00:00"))
+        );
+        // after today, so last year
+        assert_eq!(
+            Timestamp::parse_at("12-31 23:00", now).time,
+            Some(at("2022-12-31 23:00:00"))
+        );
+    }
+
+    #[test]
+    fn parse_relative_times() {
+        let now = at("2023-05-10 12:00:00");
+
+        assert_eq!(Timestamp::parse_at("剛剛", now).time, Some(now));
+        assert_eq!(
+            Timestamp::parse_at("昨天 12:34", now).time,
+            Some(at("2023-05-09 12:34:00"))
+        );
+        assert_eq!(
+            Timestamp::parse_at("3 分鐘前", now).time,
+            Some(at("2023-05-10 11:57:00"))
+        );
+        assert_eq!(
+            Timestamp::parse_at("2天前", now).time,
+            Some(at("2023-05-08 12:00:00"))
+        );
+    }
+
+    #[test]
+    fn parse_out_of_range_without_panic() {
+        let now = at("2023-05-10 12:00:00");
+
+        for raw in ["99999999999999天前", "9223372036854775807秒前", "不是時間"] {
+            let time = Timestamp::parse_at(raw, now);
+            assert_eq!(time.time, None);
+            assert_eq!(time.raw, raw);
+        }
+    }
+
+    #[test]
+    fn serde_keeps_the_parsed_time() {
+        let time = Timestamp::parse_at("昨天 12:34", at("2023-05-10 12:00:00"));
+        let json = serde_json::to_string(&time).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Timestamp>(json.as_ref()).unwrap(),
+            time
+        );
+
+        // times in the forum json are plain strings
+        let time = serde_json::from_str::<Timestamp>("\"2023-05-01 10:00:00\"").unwrap();
+        assert_eq!(time.time, Some(at("2023-05-01 10:00:00")));
+    }
+}
@@ -0,0 +1,246 @@
+use async_trait::async_trait;
+use std::time::Duration;
+
+use reqwest::{
+    header::{ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED},
+    Response, StatusCode,
+};
+use serde_json::Value;
+use url::Url;
+
+use super::{
+    disk_cache::{CacheEntry, DiskCache},
+    throttle::{retry_after, HostLimiter, RateLimit, RetryPolicy},
+    Error, Session,
+};
+
+/// How [`BahamutClient`](super::BahamutClient) fetches pages and json.
+///
+/// Implement it to add a caching proxy, a replay layer or an in memory fake,
+/// then pass it to [`BahamutClientBuilder::transport`](super::BahamutClientBuilder::transport).
+///
+/// ```
+/// use async_trait::async_trait;
+/// use bahamut::{api::Transport, Error};
+/// use url::Url;
+///
+/// struct Fake;
+///
+/// #[async_trait]
+/// impl Transport for Fake {
+///     async fn get_text(&self, _: &Url) -> Result<String, Error> {
+///         Ok("<html></html>".to_string())
+///     }
+/// }
+/// ```
+#[async_trait]
+pub trait Transport: Send + Sync {
+    /// Body of the page, missing pages should be reported as [`Error::NotFound`].
+    async fn get_text(&self, url: &Url) -> Result<String, Error>;
+
+    /// Body of the page without reading a cached copy, a conditional
+    /// request is still allowed. Only caching transports need it.
+    async fn get_text_revalidate(&self, url: &Url) -> Result<String, Error> {
+        self.get_text(url).await
+    }
+
+    async fn get_json(&self, url: &Url) -> Result<Value, Error> {
+        let json = self.get_text(url).await?;
+        Ok(serde_json::from_str::<Value>(json.as_ref())?)
+    }
+
+    /// Submit an urlencoded form and return the response body.
+    async fn post_form(&self, _url: &Url, _form: &[(&str, &str)]) -> Result<String, Error> {
+        Err(Error::Config(
+            "transport doesn't support posting forms".to_string(),
+        ))
+    }
+}
+
+/// Default transport.
+///
+/// Requests wait for the [`RateLimit`] of their host and pages are fetched
+/// again on failure following the [`RetryPolicy`]. With a [`DiskCache`],
+/// pages are read from disk while fresh and revalidated once stale, unless
+/// the [`Session`] is logged in.
+pub struct ReqwestTransport {
+    http: reqwest::Client,
+    limiter: HostLimiter,
+    retry: RetryPolicy,
+    cache: Option<DiskCache>,
+    session: Option<Session>,
+}
+
+impl ReqwestTransport {
+    /// Transport without rate limit nor disk cache, with the default retries.
+    pub fn new(http: reqwest::Client) -> ReqwestTransport {
+        ReqwestTransport {
+            http,
+            limiter: HostLimiter::new(None),
+            retry: RetryPolicy::default(),
+            cache: None,
+            session: None,
+        }
+    }
+
+    pub fn rate_limit(mut self, rate_limit: RateLimit) -> Self {
+        self.limiter = HostLimiter::new(Some(rate_limit));
+        self
+    }
+
+    pub fn retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    pub fn disk_cache(mut self, cache: DiskCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Session of the reqwest client, while it's logged in the disk cache
+    /// is skipped. The pages show what only this account sees, and a write
+    /// would leave the cached comments and votes stale.
+    pub fn session(mut self, session: Session) -> Self {
+        self.session = Some(session);
+        self
+    }
+
+    fn is_logged_in(&self) -> bool {
+        self.session
+            .as_ref()
+            .is_some_and(|session| session.is_logged_in())
+    }
+
+    /// Page from the disk cache when fresh, or fetched and stored.
+    ///
+    /// With `revalidate` a fresh page is fetched again too.
+    async fn get_page(&self, url: &Url, revalidate: bool) -> Result<String, Error> {
+        let cache = self
+            .cache
+            .as_ref()
+            .filter(|cache| cache.is_cached(url) && !self.is_logged_in());
+        let cache = match cache {
+            Some(cache) => cache,
+            None => {
+                return Ok(ReqwestTransport::body(
+                    self.get_with_retry(url, None).await?,
+                ))
+            }
+        };
+
+        let cached = cache.load(url).await;
+        if let Some(entry) = cached.as_ref() {
+            if !revalidate && cache.is_fresh(url, entry) {
+                return Ok(entry.body.clone());
+            }
+        }
+
+        let entry = match (self.get_with_retry(url, cached.as_ref()).await?, cached) {
+            (Some(entry), _) => entry,
+            (None, Some(cached)) => cached.revalidated(),
+            (None, None) => return Ok(String::new()),
+        };
+
+        // the page is fetched anyway, a cache failure isn't worth failing for
+        cache.store(url, &entry).await.unwrap_or(());
+        Ok(entry.body)
+    }
+
+    /// Fetch a page, conditionally to `cached` if any, `None` when the
+    /// server answers it's not modified.
+    async fn get_with_retry(
+        &self,
+        url: &Url,
+        cached: Option<&CacheEntry>,
+    ) -> Result<Option<CacheEntry>, Error> {
+        let mut attempt = 0;
+
+        loop {
+            let mut req = self.http.get(url.as_str());
+            if let Some(etag) = cached.and_then(|entry| entry.etag.as_ref()) {
+                req = req.header(IF_NONE_MATCH, etag);
+            }
+            if let Some(modified) = cached.and_then(|entry| entry.last_modified.as_ref()) {
+                req = req.header(IF_MODIFIED_SINCE, modified);
+            }
+
+            let (res, retry_after) = self.send(url, req).await;
+            let delay = match &res {
+                Err(e) if RetryPolicy::is_retryable(e) => self.retry.delay(attempt, retry_after),
+                _ => None,
+            };
+
+            match delay {
+                Some(delay) => tokio::time::sleep(delay).await,
+                None => return res,
+            }
+            attempt += 1;
+        }
+    }
+
+    /// Send a request once, a `Retry-After` holds the host for every request.
+    async fn send(
+        &self,
+        url: &Url,
+        req: reqwest::RequestBuilder,
+    ) -> (Result<Option<CacheEntry>, Error>, Option<Duration>) {
+        self.limiter.acquire(url).await;
+
+        let res = match req.send().await {
+            Ok(res) => res,
+            Err(e) => return (Err(e.into()), None),
+        };
+
+        let wait = retry_after(&res);
+        if let Some(wait) = wait {
+            self.limiter.pause(url, wait);
+        }
+
+        (ReqwestTransport::read_body(url, res).await, wait)
+    }
+
+    /// Body of a fetched page, empty for a 304 nobody asked for.
+    fn body(fetched: Option<CacheEntry>) -> String {
+        fetched.map(|entry| entry.body).unwrap_or_default()
+    }
+
+    async fn read_body(url: &Url, res: Response) -> Result<Option<CacheEntry>, Error> {
+        let header = |name| {
+            res.headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(String::from)
+        };
+
+        match res.status() {
+            StatusCode::NOT_MODIFIED => Ok(None),
+            StatusCode::NOT_FOUND | StatusCode::GONE => Err(Error::NotFound),
+            status if !status.is_success() => Err(Error::Status(status.as_u16())),
+            _ => {
+                let etag = header(ETAG);
+                let last_modified = header(LAST_MODIFIED);
+                let body = res.text().await?;
+                Ok(Some(CacheEntry::new(url, body, etag, last_modified)))
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Transport for ReqwestTransport {
+    async fn get_text(&self, url: &Url) -> Result<String, Error> {
+        self.get_page(url, false).await
+    }
+
+    async fn get_text_revalidate(&self, url: &Url) -> Result<String, Error> {
+        self.get_page(url, true).await
+    }
+
+    async fn post_form(&self, url: &Url, form: &[(&str, &str)]) -> Result<String, Error> {
+        let (res, _) = self
+            .send(url, self.http.post(url.as_str()).form(form))
+            .await;
+        Ok(ReqwestTransport::body(res?))
+    }
+}
@@ -1,8 +1,14 @@
 use std::fmt::Display;
 
-use scraper::{ElementRef, Selector};
+use scraper::ElementRef;
 use serde::Serialize;
 
+use super::{parse::Field, BahamutClient, Error, ParseError, ParseErrorKind};
+
+mod profile;
+
+pub use profile::{UserForumPost, UserProfile};
+
 #[derive(Clone, Serialize)]
 pub enum UserRace {
     Human,
@@ -76,51 +82,53 @@ impl Default for User {
 }
 
 impl User {
-    fn try_level_from_html(document: &ElementRef) -> Option<u8> {
-        let selector = Selector::parse(".userlevel").unwrap();
-        let lv = document
-            .select(&selector)
-            .next()?
+    /// Url of the avatar picture, it's only based on the id.
+    pub fn avatar(&self, client: &BahamutClient) -> String {
+        profile::avatar_url(client, self.id.as_ref())
+    }
+
+    fn try_level_from_html(document: &ElementRef) -> Result<u8, ParseError> {
+        let field = Field::new("User::lv", ".userlevel");
+        let lv = field
+            .first(document)?
             .text()
-            .last()?
+            .last()
+            .ok_or_else(|| field.error(ParseErrorKind::Missing))?
             .to_string();
 
-        lv.trim().parse::<u8>().map_or(Some(0u8), Some)
+        Ok(lv.trim().parse::<u8>().unwrap_or(0u8))
     }
 
-    fn try_id_from_html(document: &ElementRef) -> Option<String> {
-        let selector = Selector::parse(".c-user__avatar").unwrap();
-        let id = document
-            .select(&selector)
-            .next()?
-            .value()
-            .attr("data-gamercard-userid")?
+    fn try_id_from_html(document: &ElementRef) -> Result<String, ParseError> {
+        let field = Field::new("User::id", ".c-user__avatar");
+        let id = field
+            .attr(&field.first(document)?, "data-gamercard-userid")?
             .to_string();
 
-        Some(id)
+        Ok(id)
     }
 
-    fn try_name_from_html(document: &ElementRef) -> Option<String> {
-        let selector = Selector::parse(".username").unwrap();
-        let name = document
-            .select(&selector)
-            .next()?
+    fn try_name_from_html(document: &ElementRef) -> Result<String, ParseError> {
+        let field = Field::new("User::name", ".username");
+        let name = field
+            .first(document)?
             .text()
-            .next()?
+            .next()
+            .ok_or_else(|| field.error(ParseErrorKind::Missing))?
             .to_string();
 
-        Some(name)
+        Ok(name)
     }
 
     fn try_race_from_html(document: &ElementRef) -> Option<UserRace> {
-        let selector = Selector::parse(".userrace img").unwrap();
-        let race = document
-            .select(&selector)
-            .next()?
+        let field = Field::new("User::race", ".userrace img");
+        let race = field
+            .first(document)
+            .ok()?
             .value()
             .attr("src")?
             .split('/')
-            .last()?;
+            .next_back()?;
 
         let race = match race {
             "human.png" => UserRace::Human,
@@ -134,14 +142,14 @@ impl User {
     }
 
     fn try_crarrer_from_html(document: &ElementRef) -> Option<UserCareer> {
-        let selector = Selector::parse(".usercareer img").unwrap();
-        let crarrer = document
-            .select(&selector)
-            .next()?
+        let field = Field::new("User::carrer", ".usercareer img");
+        let crarrer = field
+            .first(document)
+            .ok()?
             .value()
             .attr("src")?
             .split('/')
-            .last()?;
+            .next_back()?;
 
         let crarrer = match crarrer {
             "noob.png" => UserCareer::Noob,
@@ -160,13 +168,13 @@ impl User {
 }
 
 impl TryFrom<&ElementRef<'_>> for User {
-    type Error = &'static str;
+    type Error = Error;
 
     fn try_from(document: &ElementRef) -> Result<Self, Self::Error> {
         let user = User {
-            lv: User::try_level_from_html(document).ok_or("user lv invalid")?,
-            id: User::try_id_from_html(document).ok_or("user id invalid")?,
-            name: User::try_name_from_html(document).ok_or("user name invalid")?,
+            lv: User::try_level_from_html(document)?,
+            id: User::try_id_from_html(document)?,
+            name: User::try_name_from_html(document)?,
             race: User::try_race_from_html(document).map_or(UserRace::Unknown, |x| x),
             carrer: User::try_crarrer_from_html(document).map_or(UserCareer::Unknown, |x| x),
         };
@@ -0,0 +1,252 @@
+use scraper::{ElementRef, Html};
+use serde::Serialize;
+use url::Url;
+
+use crate::api::{
+    get_document, is_missing_page, parse::Field, BahamutClient, Error, ParseError, ParseErrorKind,
+    Timestamp, UrlWithId, WebSite,
+};
+
+use super::User;
+
+/// A forum post listed on the home of its author.
+#[derive(Clone, Default, Serialize)]
+pub struct UserForumPost {
+    pub title: String,
+    pub url: String,
+}
+
+/// Profile shown on the home (小屋) of a user.
+#[derive(Clone, Default, Serialize)]
+pub struct UserProfile {
+    pub id: String,
+    pub name: String,
+    /// 稱號
+    pub title: String,
+    pub lv: u16,
+    pub gp: u32,
+    pub exp: u32,
+    pub joined: Timestamp,
+    pub avatar: String,
+    pub signature: String,
+    /// Latest forum posts, newest first
+    pub posts: Vec<UserForumPost>,
+
+    /// Fields which can't be read and were left to their default
+    pub parse_errors: Vec<ParseError>,
+}
+
+impl UrlWithId<&str> for UserProfile {
+    fn url(base: &Url, id: &str) -> Url {
+        let url = format!("{}?owner={}", "homeindex.php", id);
+        base.join(url.as_ref()).expect("invalid url")
+    }
+}
+
+impl User {
+    /// Fetch the profile of this user.
+    pub async fn get_profile(&self, client: &BahamutClient) -> Result<UserProfile, Error> {
+        UserProfile::get_profile(client, self.id.as_ref()).await
+    }
+}
+
+impl UserProfile {
+    pub async fn get_profile(client: &BahamutClient, id: &str) -> Result<UserProfile, Error> {
+        let url = UserProfile::url(&client.home_url(""), id);
+        let document = get_document(client, &url).await?;
+        let mut profile = UserProfile::try_from(WebSite { url, document })?;
+
+        // the avatar url only depends on the id, it's parsed in case it moves
+        if profile.avatar.is_empty() {
+            profile.avatar = avatar_url(client, id);
+        }
+        Ok(profile)
+    }
+
+    /// Parse a saved `homeindex.php` page, `url` must contain the `owner` query.
+    pub fn from_html(html: &str, url: &Url) -> Result<UserProfile, Error> {
+        UserProfile::try_from(WebSite::from_html(html, url))
+    }
+
+    /// `<title>` of the home is `name的小屋`.
+    fn try_name_from_html(document: &Html) -> Result<String, ParseError> {
+        let field = Field::new("UserProfile::name", "head title");
+        let title = field.text(&document.root_element())?;
+        title
+            .split_once("的小屋")
+            .map(|(name, _)| name.trim().to_string())
+            .filter(|name| !name.is_empty())
+            .ok_or_else(|| field.error(ParseErrorKind::Missing))
+    }
+
+    fn try_avatar_from_html(document: &Html, base: &Url) -> Result<String, ParseError> {
+        let field = Field::new("UserProfile::avatar", "img[src*=\"avataruserpic\"]");
+        let src = field.attr(&field.first(&document.root_element())?, "src")?;
+        field.url(base, src).map(String::from)
+    }
+
+    fn try_posts_from_html(document: &Html, base: &Url) -> Result<Vec<UserForumPost>, ParseError> {
+        let field = Field::new("UserProfile::posts", "a[href*=\"C.php?\"]");
+        let mut posts: Vec<UserForumPost> = vec![];
+
+        field
+            .all(&document.root_element())?
+            .into_iter()
+            .for_each(|a| {
+                let title = a.text().collect::<String>().trim().to_string();
+                let url = field
+                    .attr(&a, "href")
+                    .and_then(|href| field.url(base, href));
+
+                if let (false, Ok(url)) = (title.is_empty(), url) {
+                    let url = url.to_string();
+                    if !posts.iter().any(|p| p.url == url) {
+                        posts.push(UserForumPost { title, url });
+                    }
+                }
+            });
+
+        Ok(posts)
+    }
+
+    /// Value after the first of `labels`, as in `GP：123` or a label and its
+    /// value in separate elements.
+    fn try_labeled_from_texts(
+        texts: &[String],
+        field: Field,
+        labels: &[&'static str],
+    ) -> Result<String, ParseError> {
+        texts
+            .iter()
+            .enumerate()
+            .find_map(|(i, text)| {
+                let label = labels.iter().find(|l| text.starts_with(*l))?;
+                let value = text[label.len()..]
+                    .trim_start_matches(|c: char| {
+                        c.is_whitespace() || c == ':' || c == '：' || c == '.'
+                    })
+                    .trim();
+
+                if value.is_empty() {
+                    texts.get(i + 1).cloned()
+                } else {
+                    Some(value.to_string())
+                }
+            })
+            .ok_or_else(|| field.error(ParseErrorKind::Label(labels[0])))
+    }
+
+    /// Leading number of a labeled value, `1,234 (排名 5)` is `1234`.
+    fn try_number_from_texts<T: std::str::FromStr>(
+        texts: &[String],
+        field: Field,
+        labels: &[&'static str],
+    ) -> Result<T, ParseError> {
+        let value = UserProfile::try_labeled_from_texts(texts, field, labels)?;
+        let number = value
+            .chars()
+            .take_while(|c| c.is_ascii_digit() || *c == ',')
+            .filter(|c| *c != ',')
+            .collect::<String>();
+        field.number(&number)
+    }
+}
+
+/// Trimmed text nodes of the body, without scripts and styles.
+fn body_texts(document: &Html) -> Vec<String> {
+    let Ok(body) = Field::new("UserProfile::body", "body").first(&document.root_element()) else {
+        return vec![];
+    };
+
+    body.descendants()
+        .filter(|node| {
+            !node
+                .parent()
+                .and_then(ElementRef::wrap)
+                .is_some_and(|e| matches!(e.value().name(), "script" | "style"))
+        })
+        .filter_map(|node| node.value().as_text().map(|t| t.trim().to_string()))
+        .filter(|text| !text.is_empty())
+        .collect()
+}
+
+/// Avatar of a user, it's only based on the id.
+pub(crate) fn avatar_url(client: &BahamutClient, id: &str) -> String {
+    let id = id.to_lowercase();
+    let mut chars = id.chars();
+    let first = chars.next().map(String::from).unwrap_or_default();
+    let second = chars.next().map(String::from).unwrap_or_default();
+    let path = format!("avataruserpic/{}/{}/{}/{}_s.png", first, second, id, id);
+    client.avatar_url(path.as_ref()).to_string()
+}
+
+impl TryFrom<WebSite> for UserProfile {
+    type Error = Error;
+
+    fn try_from(web: WebSite) -> Result<Self, Error> {
+        let WebSite { document, url } = web;
+
+        let mut profile = UserProfile {
+            id: Field::new("UserProfile::id", "owner").query(&url)?,
+            ..UserProfile::default()
+        };
+
+        match UserProfile::try_name_from_html(&document) {
+            Ok(name) => profile.name = name,
+            Err(_) if is_missing_page(&document) => return Err(Error::NotFound),
+            Err(e) => profile.parse_errors.push(e),
+        };
+
+        let texts = body_texts(&document);
+
+        let field = Field::new("UserProfile::title", "body");
+        match UserProfile::try_labeled_from_texts(&texts, field, &["稱號"]) {
+            Ok(title) => profile.title = title,
+            Err(e) => profile.parse_errors.push(e),
+        };
+
+        let field = Field::new("UserProfile::lv", "body");
+        match UserProfile::try_number_from_texts(&texts, field, &["LV", "Lv", "等級"]) {
+            Ok(lv) => profile.lv = lv,
+            Err(e) => profile.parse_errors.push(e),
+        };
+
+        let field = Field::new("UserProfile::gp", "body");
+        match UserProfile::try_number_from_texts(&texts, field, &["GP"]) {
+            Ok(gp) => profile.gp = gp,
+            Err(e) => profile.parse_errors.push(e),
+        };
+
+        let field = Field::new("UserProfile::exp", "body");
+        match UserProfile::try_number_from_texts(&texts, field, &["經驗", "EXP"]) {
+            Ok(exp) => profile.exp = exp,
+            Err(e) => profile.parse_errors.push(e),
+        };
+
+        let field = Field::new("UserProfile::joined", "body");
+        match UserProfile::try_labeled_from_texts(&texts, field, &["註冊", "創立", "加入"]) {
+            Ok(joined) => profile.joined = Timestamp::parse(joined.as_ref()),
+            Err(e) => profile.parse_errors.push(e),
+        };
+
+        // `get_profile` falls back to the url built from the id
+        match UserProfile::try_avatar_from_html(&document, &url) {
+            Ok(avatar) => profile.avatar = avatar,
+            Err(e) => profile.parse_errors.push(e),
+        };
+
+        match Field::new("UserProfile::signature", "[class*=\"signature\"]")
+            .text(&document.root_element())
+        {
+            Ok(signature) => profile.signature = signature,
+            Err(e) => profile.parse_errors.push(e),
+        };
+
+        match UserProfile::try_posts_from_html(&document, &url) {
+            Ok(posts) => profile.posts = posts,
+            Err(e) => profile.parse_errors.push(e),
+        };
+
+        Ok(profile)
+    }
+}
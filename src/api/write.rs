@@ -0,0 +1,244 @@
+//! Forms sent by a logged in session, every one carries a CSRF token.
+//!
+//! Point [`BahamutClientBuilder::base_url`](super::BahamutClientBuilder::base_url)
+//! to a local stand-in server to try them without posting on the forum, with
+//! a [`Session::with_domain`](super::Session::with_domain) of its host so the
+//! login cookies are sent to it.
+
+use serde_json::Value;
+use url::Url;
+
+use super::{BahamutClient, Error};
+
+static CSRF_TOKEN_PATH: &str = "ajax/get_csrf_token.php";
+/// Form field of the CSRF token
+static CSRF_TOKEN_KEY: &str = "token";
+
+/// Token the forum expects in every form, it's bound to the session cookies.
+pub(crate) async fn csrf_token(client: &BahamutClient) -> Result<String, Error> {
+    let url = client.url(CSRF_TOKEN_PATH);
+    let token = client.transport().get_text(&url).await?;
+    let token = token.trim();
+
+    if token.is_empty() {
+        Err(Error::Auth("no csrf token, session expired".to_string()))
+    } else {
+        Ok(token.to_string())
+    }
+}
+
+/// Post `form` with a fresh CSRF token and read the json answer.
+///
+/// Answers with an error message are mapped to [`Error::FloodControl`],
+/// [`Error::PermissionDenied`], [`Error::Auth`] or [`Error::Rejected`].
+pub(crate) async fn submit(
+    client: &BahamutClient,
+    url: &Url,
+    form: &[(&str, &str)],
+) -> Result<Value, Error> {
+    if !client.session().is_logged_in() {
+        return Err(Error::Auth("not logged in".to_string()));
+    }
+
+    let token = csrf_token(client).await?;
+    let mut form = form.to_vec();
+    form.push((CSRF_TOKEN_KEY, token.as_ref()));
+
+    let body = match client.transport().post_form(url, &form).await {
+        Ok(body) => body,
+        Err(Error::Status(429)) => {
+            return Err(Error::FloodControl("too many requests".to_string()))
+        }
+        Err(Error::Status(403)) => return Err(Error::PermissionDenied("forbidden".to_string())),
+        Err(e) => return Err(e),
+    };
+
+    let res = serde_json::from_str::<Value>(body.as_ref())?;
+    match error_message(&res) {
+        Some(msg) => Err(error_from_message(msg)),
+        None => Ok(res),
+    }
+}
+
+/// `{"error": {"message": ..}}`, a non empty `error` text or a non zero
+/// `error` or `code` with a `msg`. `"error": 0`, `null` or `false` are
+/// successes.
+fn error_message(res: &Value) -> Option<String> {
+    let message = |value: &Value| {
+        value
+            .get("message")
+            .or_else(|| value.get("msg"))
+            .and_then(Value::as_str)
+            .map(String::from)
+    };
+
+    match res.get("error") {
+        Some(error @ Value::Object(_)) => {
+            return Some(message(error).unwrap_or_else(|| "unknown error".to_string()))
+        }
+        Some(Value::String(error)) if !error.trim().is_empty() => return Some(error.to_owned()),
+        Some(Value::Number(n)) if n.as_f64().is_some_and(|n| n != 0.0) => {
+            return Some(message(res).unwrap_or_else(|| format!("error code {}", n)))
+        }
+        _ => (),
+    }
+
+    let code = res.get("code").and_then(|code| match code {
+        Value::Number(n) => n.as_i64(),
+        Value::String(s) => s.parse::<i64>().ok(),
+        _ => None,
+    });
+
+    match code {
+        Some(code) if code != 0 => {
+            Some(message(res).unwrap_or_else(|| format!("error code {}", code)))
+        }
+        _ => None,
+    }
+}
+
+fn error_from_message(msg: String) -> Error {
+    let is = |words: &[&str]| words.iter().any(|w| msg.contains(w));
+
+    if is(&["太快", "頻繁", "稍後", "秒後"]) {
+        Error::FloodControl(msg)
+    } else if is(&["登入", "驗證"]) {
+        Error::Auth(msg)
+    } else if is(&["權限", "禁止", "鎖定", "水桶"]) {
+        Error::PermissionDenied(msg)
+    } else {
+        Error::Rejected(msg)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use async_trait::async_trait;
+    use serde_json::json;
+
+    use super::*;
+    use crate::api::{post::PostComment, Session, Transport, COOKIE_TOKEN};
+
+    type Forms = Arc<Mutex<Vec<(String, Vec<(String, String)>)>>>;
+
+    /// Stand-in forum answering every form with `answer`.
+    struct Fake {
+        answer: String,
+        forms: Forms,
+    }
+
+    #[async_trait]
+    impl Transport for Fake {
+        async fn get_text(&self, url: &Url) -> Result<String, Error> {
+            match url.path() {
+                "/ajax/get_csrf_token.php" => Ok("csrf".to_string()),
+                _ => Err(Error::NotFound),
+            }
+        }
+
+        async fn post_form(&self, url: &Url, form: &[(&str, &str)]) -> Result<String, Error> {
+            let form = form
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect();
+            self.forms
+                .lock()
+                .unwrap()
+                .push((url.path().to_string(), form));
+            Ok(self.answer.clone())
+        }
+    }
+
+    fn stand_in(answer: Value, logged_in: bool) -> (BahamutClient, Forms) {
+        let forms = Forms::default();
+        let session = Session::new();
+        if logged_in {
+            session.set_cookie(COOKIE_TOKEN, "token");
+        }
+
+        let client = BahamutClient::builder()
+            .session(session)
+            .transport(Fake {
+                answer: answer.to_string(),
+                forms: forms.clone(),
+            })
+            .build()
+            .unwrap();
+        (client, forms)
+    }
+
+    #[test]
+    fn error_message_values() {
+        for ok in [
+            json!({"error": 0}),
+            json!({"error": null}),
+            json!({"error": false}),
+            json!({"error": ""}),
+            json!({"code": "0", "msg": "ok"}),
+            json!({"ok": 1}),
+        ] {
+            assert_eq!(error_message(&ok), None, "{}", ok);
+        }
+
+        let error = |v: Value| error_message(&v);
+        assert_eq!(
+            error(json!({"error": {"message": "no"}})),
+            Some("no".to_string())
+        );
+        assert_eq!(
+            error(json!({"error": {}})),
+            Some("unknown error".to_string())
+        );
+        assert_eq!(error(json!({"error": "bad"})), Some("bad".to_string()));
+        assert_eq!(error(json!({"error": 2})), Some("error code 2".to_string()));
+        assert_eq!(
+            error(json!({"code": 1, "msg": "rejected"})),
+            Some("rejected".to_string())
+        );
+    }
+
+    #[test]
+    fn errors_from_messages() {
+        let kind = |msg: &str| match error_from_message(msg.to_string()) {
+            Error::FloodControl(_) => "flood",
+            Error::Auth(_) => "auth",
+            Error::PermissionDenied(_) => "permission",
+            Error::Rejected(_) => "rejected",
+            _ => "other",
+        };
+
+        assert_eq!(kind("留言太頻繁，請稍後再試"), "flood");
+        assert_eq!(kind("請先登入"), "auth");
+        assert_eq!(kind("您沒有權限"), "permission");
+        assert_eq!(kind("本文已鎖定"), "permission");
+        assert_eq!(kind("無法留言，內容不是有效的文字"), "rejected");
+    }
+
+    #[tokio::test]
+    async fn send_comment_to_stand_in() {
+        let (client, forms) = stand_in(json!({"error": 0}), true);
+        PostComment::send_comment(&client, "60076", "12", "hi")
+            .await
+            .unwrap();
+
+        let forms = forms.lock().unwrap();
+        let (path, form) = &forms[0];
+        assert_eq!(path, "/ajax/doComment.php");
+        assert!(form.contains(&("content".to_string(), "hi".to_string())));
+        assert!(form.contains(&("token".to_string(), "csrf".to_string())));
+    }
+
+    #[tokio::test]
+    async fn submit_maps_refusals() {
+        let (client, _) = stand_in(json!({"error": {"message": "發文太快"}}), true);
+        let res = PostComment::send_comment(&client, "60076", "12", "hi").await;
+        assert!(matches!(res, Err(Error::FloodControl(_))));
+
+        let (client, forms) = stand_in(json!({"error": 0}), false);
+        let res = PostComment::send_comment(&client, "60076", "12", "hi").await;
+        assert!(matches!(res, Err(Error::Auth(_))));
+        assert!(forms.lock().unwrap().is_empty());
+    }
+}
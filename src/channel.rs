@@ -1,7 +1,8 @@
 use bahamut::api::{
-    board::BoardPost,
-    post::{Post, PostComment},
-    search::SearchResult,
+    board::{BoardCategory, BoardInfo, BoardPost, BoardPostQuery, BoardSort},
+    post::{Post, PostComment, Vote, VoteResult},
+    search::{ForumHot, SearchResult},
+    user::UserProfile,
 };
 
 pub struct PageData<T> {
@@ -10,17 +11,52 @@ pub struct PageData<T> {
     pub items: T,
 }
 
+/// Which posts of a board to list.
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
+pub struct BoardFilter {
+    pub query: Option<BoardPostQuery>,
+    /// `subbsn` of the category
+    pub category: Option<String>,
+    pub sort: BoardSort,
+}
+
+/// What a vote is cast on.
+#[derive(Clone)]
+pub enum VoteTarget {
+    Floor(u16),
+    /// `sn` of the comment
+    Comment(String),
+}
+
 pub enum FetchDataMsg {
     SearchResult(Vec<SearchResult>),
-    BoardPage(PageData<Vec<BoardPost>>),
+    Hot(ForumHot),
+    /// Subscribed boards, or why they can't be loaded
+    MyBoards(Result<Vec<SearchResult>, String>),
+    BoardPage(PageData<Vec<BoardPost>>, Vec<BoardCategory>),
+    BoardInfo(Option<BoardInfo>),
     PostPage(PageData<Post>),
+    /// Page of the floor to focus, `None` if it can't be loaded
+    PostFloor(Option<PageData<Post>>, u16),
     CommentPage(Vec<PostComment>),
+    UserProfile(Option<UserProfile>),
+    /// The vote, or why it's refused
+    Vote(VoteTarget, Result<VoteResult, String>),
 }
 
 pub enum DataRequestMsg {
     SearchResult(String),
-    BoardPage(String, u16, bool),
+    /// Front page rankings
+    Hot,
+    MyBoards,
+    BoardPage(String, u16, bool, BoardFilter),
+    BoardInfo(String),
     PostPage(String, u16, bool),
+    PostFloor(String, u16),
     CommentPage(String, String),
+    UserProfile(String),
+    /// Board id, floor id (`snB`) and floor number
+    VoteFloor(String, String, u16, Vote),
+    VoteComment(Box<PostComment>, Vote),
     End,
 }
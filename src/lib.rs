@@ -1 +1,3 @@
 pub mod api;
+
+pub use api::Error;
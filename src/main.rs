@@ -1,22 +1,22 @@
-extern crate lazy_static;
-
 use std::{
-    cell::RefCell,
     collections::HashMap,
     error::Error,
-    io,
+    fs, io,
+    path::PathBuf,
     sync::mpsc::{channel, Receiver, Sender},
     thread::{self, JoinHandle},
     time::Duration,
 };
 
 use bahamut::api::{
-    board::BoardPage,
-    post::{Post, PostComment, PostPage, PostPageUrlParameter},
-    search::BoardSearch,
-    CachedPage,
+    account::Account,
+    board::{BoardInfo, BoardPage},
+    post::{PostComment, PostContent, PostPage, PostPageUrlParameter, VoteResult},
+    search::{BoardSearch, ForumHot},
+    user::UserProfile,
+    BahamutClient, CachedPage, DiskCache, Session,
 };
-use channel::{DataRequestMsg, FetchDataMsg, PageData};
+use channel::{BoardFilter, DataRequestMsg, FetchDataMsg, PageData, VoteTarget};
 use crossterm::{
     event::{self, DisableMouseCapture, Event},
     execute,
@@ -40,6 +40,7 @@ mod ui;
 async fn main() -> Result<(), Box<dyn Error>> {
     let (tx_req, rx_req) = channel::<DataRequestMsg>();
     let (tx_rev, rx_rev) = channel::<FetchDataMsg>();
+    let session = load_session();
 
     // setup terminal
     enable_raw_mode()?;
@@ -49,15 +50,18 @@ async fn main() -> Result<(), Box<dyn Error>> {
     let mut terminal = Terminal::new(backend)?;
 
     // fetch thread
-    let fetcher = run_fetcher(tx_rev, rx_req);
+    let fetcher = run_fetcher(session.clone(), tx_rev, rx_req);
 
-    // ui thread
-    let app = AppState::new();
+    // ui thread, starts with the front page rankings
+    let mut app = AppState::new();
+    app.loading = true;
+    tx_req.send(DataRequestMsg::Hot).unwrap_or(());
     let res = run_app(&mut terminal, app, tx_req.clone(), rx_rev);
 
     // close fetch thread
     tx_req.send(DataRequestMsg::End).unwrap_or(());
     fetcher.join().unwrap_or(());
+    save_session(&session);
 
     // restore terminal
     disable_raw_mode()?;
@@ -75,6 +79,35 @@ async fn main() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+fn config_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("bahamut"))
+}
+
+/// Saved session, or the cookies exported from a browser to `cookies.txt`
+/// in the config dir.
+fn load_session() -> Session {
+    let Some(dir) = config_dir() else {
+        return Session::new();
+    };
+
+    Session::load(dir.join("session.json"))
+        .or_else(|_| Session::from_cookies_txt(dir.join("cookies.txt")))
+        .unwrap_or_default()
+}
+
+fn save_session(session: &Session) {
+    let Some(dir) = config_dir() else {
+        return;
+    };
+
+    // drop an expired session so the next run can import cookies.txt again
+    if !session.is_logged_in() {
+        fs::remove_file(dir.join("session.json")).unwrap_or(());
+    } else if fs::create_dir_all(&dir).is_ok() {
+        session.save(dir.join("session.json")).unwrap_or(());
+    }
+}
+
 fn run_app<B: Backend>(
     terminal: &mut Terminal<B>,
     mut app: AppState,
@@ -100,13 +133,27 @@ fn run_app<B: Backend>(
                     app.search.init_select();
                     app.page = Page::Search;
                 }
-                FetchDataMsg::BoardPage(v) => {
+                FetchDataMsg::Hot(v) => {
+                    app.search.hot(v.boards, v.posts);
+                    app.search.init_select();
+                }
+                FetchDataMsg::MyBoards(v) => {
+                    app.my_boards.items(v);
+                    app.my_boards.init_select();
+                    app.page = Page::MyBoards;
+                }
+                FetchDataMsg::BoardPage(v, categories) => {
+                    app.board.categories(categories);
                     app.board.items(v.items);
                     app.board.init_select();
                     app.board.last_page(v.max);
                     app.board.page(v.page);
                     app.page = Page::Board;
                 }
+                FetchDataMsg::BoardInfo(v) => {
+                    app.board.show_info = v.is_some();
+                    app.board.info = v;
+                }
                 FetchDataMsg::PostPage(v) => {
                     if v.page == 1 {
                         app.post.data(v.items);
@@ -120,10 +167,35 @@ fn run_app<B: Backend>(
                         app.post.next();
                     }
                 }
+                FetchDataMsg::PostFloor(v, floor) => {
+                    if let Some(v) = v {
+                        app.post.data(v.items);
+                        app.post.page(v.page);
+                        app.post.last_page(v.max);
+                        if !app.post.jump_to_floor(floor) {
+                            app.post.index(0);
+                        }
+                    }
+                }
                 FetchDataMsg::CommentPage(v) => {
                     app.page = Page::Comment;
                     app.comment.items(v);
                 }
+                FetchDataMsg::Vote(target, v) => {
+                    app.status = Some(match v {
+                        Ok(v) => {
+                            apply_vote(&mut app, &target, &v);
+                            "投票成功".to_string()
+                        }
+                        Err(e) => format!("無法投票: {}", e),
+                    });
+                }
+                FetchDataMsg::UserProfile(v) => {
+                    if let Some(profile) = v {
+                        app.user.profile(profile);
+                        app.page = Page::User;
+                    }
+                }
             }
 
             app.loading = false;
@@ -131,10 +203,45 @@ fn run_app<B: Backend>(
     }
 }
 
-fn run_fetcher(tx: Sender<FetchDataMsg>, rx: Receiver<DataRequestMsg>) -> JoinHandle<()> {
+fn apply_vote(app: &mut AppState, target: &VoteTarget, vote: &VoteResult) {
+    match target {
+        VoteTarget::Floor(floor) => {
+            if let Some(post) = app.post.data.posts.iter_mut().find(|p| p.floor == *floor) {
+                vote.apply(&mut post.gp, &mut post.bp);
+            }
+        }
+        VoteTarget::Comment(sn) => {
+            if let Some(comment) = app.comment.items.iter_mut().find(|c| &c.sn == sn) {
+                vote.apply(&mut comment.gp, &mut comment.bp);
+            }
+        }
+    }
+}
+
+/// Reason of a refused form, the message of the forum when there's one.
+fn refused_reason(e: bahamut::Error) -> String {
+    match e {
+        bahamut::Error::Auth(_) => "請先登入".to_string(),
+        bahamut::Error::FloodControl(msg)
+        | bahamut::Error::PermissionDenied(msg)
+        | bahamut::Error::Rejected(msg) => msg,
+        e => e.to_string(),
+    }
+}
+
+fn run_fetcher(
+    session: Session,
+    tx: Sender<FetchDataMsg>,
+    rx: Receiver<DataRequestMsg>,
+) -> JoinHandle<()> {
     thread::spawn(move || {
-        let mut board_cache: HashMap<String, RefCell<BoardPage>> = HashMap::new();
-        let mut post_cache: HashMap<String, RefCell<PostPage>> = HashMap::new();
+        let mut board_cache: HashMap<(String, BoardFilter), BoardPage> = HashMap::new();
+        let mut post_cache: HashMap<String, PostPage> = HashMap::new();
+        let mut builder = BahamutClient::builder().session(session);
+        if let Some(cache) = DiskCache::in_cache_dir() {
+            builder = builder.disk_cache(cache);
+        }
+        let client = builder.build().expect("build client fail");
 
         let rt = Builder::new_multi_thread().enable_all().build().unwrap();
         rt.block_on(async {
@@ -143,47 +250,85 @@ fn run_fetcher(tx: Sender<FetchDataMsg>, rx: Receiver<DataRequestMsg>) -> JoinHa
                     match msg {
                         DataRequestMsg::End => return,
                         DataRequestMsg::SearchResult(query) => {
-                            let res = BoardSearch::get_search_result(query.as_ref());
+                            let res = BoardSearch::get_search_result(&client, query.as_ref())
+                                .await
+                                .unwrap_or_default();
                             if tx.send(FetchDataMsg::SearchResult(res)).is_err() {
                                 println!("get search result error")
                             };
                         }
 
+                        DataRequestMsg::Hot => {
+                            let res = ForumHot::get_hot(&client).await.unwrap_or_default();
+                            tx.send(FetchDataMsg::Hot(res)).unwrap_or(());
+                        }
+
+                        DataRequestMsg::MyBoards => {
+                            let res = Account::get_subscribed_boards(&client)
+                                .await
+                                .map_err(refused_reason);
+                            tx.send(FetchDataMsg::MyBoards(res)).unwrap_or(());
+                        }
+
                         // board page request
-                        DataRequestMsg::BoardPage(id, page, use_cache) => {
+                        DataRequestMsg::BoardPage(id, page, use_cache, filter) => {
+                            let cache_key = (id.to_owned(), filter.clone());
+
                             if use_cache {
-                                if let Some(board_page) = board_cache.get(&id) {
-                                    let mut board_page = board_page.borrow_mut();
-                                    if let Some(board) = board_page.get_and_cache(page, false) {
+                                if let Some(board_page) = board_cache.get_mut(&cache_key) {
+                                    if let Ok(board) = board_page.get_and_cache(page, false).await {
                                         let items = board.post();
                                         let page_data = PageData {
                                             page,
                                             items,
                                             max: board_page.max,
                                         };
-                                        tx.send(FetchDataMsg::BoardPage(page_data))
-                                            .map_or((), |_| ());
+                                        tx.send(FetchDataMsg::BoardPage(
+                                            page_data,
+                                            board.categories(),
+                                        ))
+                                        .unwrap_or(());
                                         continue;
                                     }
                                 }
                             }
 
-                            let mut board = BoardPage::from_page(id.as_ref(), page);
-                            board.init();
+                            let mut board = BoardPage::from_page(&client, id.as_ref(), page);
+                            board.query = filter.query;
+                            board.category = filter.category;
+                            board.sort = filter.sort;
+                            board.init().await.unwrap_or(());
 
-                            let items = match board.get_and_cache(page, false) {
-                                Some(board) => board.post(),
-                                None => vec![],
-                            };
+                            let (items, categories) =
+                                match board.get_and_cache(page, !use_cache).await {
+                                    Ok(board) => (board.post(), board.categories()),
+                                    Err(_) => (vec![], vec![]),
+                                };
 
                             let page_data = PageData {
                                 page,
                                 items,
                                 max: board.max,
                             };
-                            board_cache.insert(id, RefCell::new(board));
-                            tx.send(FetchDataMsg::BoardPage(page_data))
-                                .map_or((), |_| ());
+                            board_cache.insert(cache_key, board);
+                            tx.send(FetchDataMsg::BoardPage(page_data, categories))
+                                .unwrap_or(());
+                        }
+
+                        // board info, read from the cached first page if any
+                        DataRequestMsg::BoardInfo(id) => {
+                            let cache_key = (id.to_owned(), BoardFilter::default());
+                            let cached = match board_cache.get(&cache_key) {
+                                Some(board_page) => board_page.get(1, false).await.ok(),
+                                None => None,
+                            };
+
+                            let info = match cached {
+                                Some(board) => board.info(),
+                                None => BoardInfo::get_info(&client, id.as_ref()).await,
+                            };
+
+                            tx.send(FetchDataMsg::BoardInfo(info.ok())).unwrap_or(());
                         }
 
                         // post page request
@@ -191,49 +336,103 @@ fn run_fetcher(tx: Sender<FetchDataMsg>, rx: Receiver<DataRequestMsg>) -> JoinHa
                             let cache_key = url.to_owned();
 
                             if use_cache {
-                                if let Some(post_page) = post_cache.get(&cache_key) {
-                                    let mut post_page = post_page.borrow_mut();
-                                    if let Some(post) = post_page.get_and_cache(page, false) {
+                                if let Some(post_page) = post_cache.get_mut(&cache_key) {
+                                    if let Ok(post) = post_page.get_and_cache(page, false).await {
                                         let page_data = PageData {
                                             page,
                                             items: post,
                                             max: post_page.max,
                                         };
-                                        tx.send(FetchDataMsg::PostPage(page_data))
-                                            .map_or((), |_| ());
+                                        tx.send(FetchDataMsg::PostPage(page_data)).unwrap_or(());
                                         continue;
                                     }
                                 }
                             }
 
                             let param = PostPageUrlParameter::try_from(url).unwrap();
-                            let mut post_page = PostPage::try_from(param).unwrap();
-                            post_page.init();
+                            let mut post_page = PostPage::try_from((&client, param)).unwrap();
+                            post_page.init().await.unwrap_or(());
 
-                            let items = match post_page.get_and_cache(page, false) {
-                                None => Post::default(),
-                                Some(post) => post,
-                            };
+                            let items = post_page
+                                .get_and_cache(page, !use_cache)
+                                .await
+                                .unwrap_or_default();
 
                             let page_data = PageData {
                                 page,
                                 items,
                                 max: post_page.max,
                             };
-                            post_cache.insert(cache_key, RefCell::new(post_page));
-                            tx.send(FetchDataMsg::PostPage(page_data))
-                                .map_or((), |_| ())
+                            post_cache.insert(cache_key, post_page);
+                            tx.send(FetchDataMsg::PostPage(page_data)).unwrap_or(())
+                        }
+
+                        // jump to floor
+                        DataRequestMsg::PostFloor(url, floor) => {
+                            if !post_cache.contains_key(&url) {
+                                let post_page = PostPageUrlParameter::try_from(url.to_owned())
+                                    .and_then(|param| PostPage::try_from((&client, param)));
+                                if let Ok(mut post_page) = post_page {
+                                    post_page.init().await.unwrap_or(());
+                                    post_cache.insert(url.to_owned(), post_page);
+                                }
+                            }
+
+                            let mut page_data = None;
+                            if let Some(post_page) = post_cache.get_mut(&url) {
+                                if let Ok(pos) = post_page.locate_floor(floor) {
+                                    if let Ok(post) = post_page.get_and_cache(pos.page, false).await
+                                    {
+                                        page_data = Some(PageData {
+                                            page: pos.page,
+                                            items: post,
+                                            max: post_page.max,
+                                        });
+                                    }
+                                }
+                            }
+
+                            tx.send(FetchDataMsg::PostFloor(page_data, floor))
+                                .unwrap_or(());
                         }
 
                         // comment
                         DataRequestMsg::CommentPage(id, c_id) => {
-                            let res = match PostComment::get_comment(id.to_owned(), c_id.to_owned())
+                            // fall back to the first batch if a later one fails
+                            let res = match PostComment::get_all_comment(
+                                &client,
+                                id.to_owned(),
+                                c_id.to_owned(),
+                            )
+                            .await
                             {
-                                Ok(v) => v,
-                                Err(_) => vec![],
+                                Ok(res) => res,
+                                Err(_) => PostComment::get_comment(&client, id, c_id)
+                                    .await
+                                    .unwrap_or_default(),
                             };
 
-                            tx.send(FetchDataMsg::CommentPage(res)).map_or((), |_| ());
+                            tx.send(FetchDataMsg::CommentPage(res)).unwrap_or(());
+                        }
+
+                        // votes
+                        DataRequestMsg::VoteFloor(id, c_id, floor, vote) => {
+                            let res = PostContent::vote(&client, id.as_ref(), c_id.as_ref(), vote)
+                                .await
+                                .map_err(refused_reason);
+                            tx.send(FetchDataMsg::Vote(VoteTarget::Floor(floor), res))
+                                .unwrap_or(());
+                        }
+                        DataRequestMsg::VoteComment(comment, vote) => {
+                            let res = comment.vote(&client, vote).await.map_err(refused_reason);
+                            tx.send(FetchDataMsg::Vote(VoteTarget::Comment(comment.sn), res))
+                                .unwrap_or(());
+                        }
+
+                        // user profile
+                        DataRequestMsg::UserProfile(id) => {
+                            let res = UserProfile::get_profile(&client, id.as_ref()).await;
+                            tx.send(FetchDataMsg::UserProfile(res.ok())).unwrap_or(());
                         }
                     };
                 };
@@ -1,11 +1,13 @@
 use ratatui::{
     layout::{Alignment, Constraint, Layout},
-    style::{Modifier, Style},
-    text::Line,
-    widgets::{Block, Borders, List, ListItem, StatefulWidget, Widget},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, Paragraph, StatefulWidget, Widget, Wrap},
 };
 
-use super::state::BoardPageState;
+use bahamut::api::board::{BoardInfo, BoardPost, BoardSort};
+
+use super::state::{BoardPageState, InputMode};
 
 pub struct BoardPageUI;
 
@@ -18,31 +20,155 @@ impl StatefulWidget for BoardPageUI {
         buf: &mut ratatui::buffer::Buffer,
         state: &mut Self::State,
     ) {
+        let tags_height = if state.categories.is_empty() { 0 } else { 1 };
         let layout = Layout::default()
-            .constraints([Constraint::Min(0), Constraint::Length(1)])
+            .constraints([
+                Constraint::Length(tags_height),
+                Constraint::Min(0),
+                Constraint::Length(1),
+            ])
             .horizontal_margin(1)
             .split(area);
 
+        // categories, the current one is highlighted
+        let selected_style = Style::default().add_modifier(Modifier::REVERSED);
+        let tag_style = |selected: bool| {
+            if selected {
+                selected_style
+            } else {
+                Style::default().fg(Color::DarkGray)
+            }
+        };
+        let mut tags = vec![Span::styled(
+            " 全部 ",
+            tag_style(state.filter.category.is_none()),
+        )];
+        state.categories.iter().for_each(|c| {
+            let selected = state.filter.category.as_ref() == Some(&c.id());
+            tags.push(Span::styled(format!(" {} ", c.name), tag_style(selected)));
+        });
+        Paragraph::new(Line::from(tags)).render(layout[0], buf);
+
+        let title = match &state.filter.query {
+            Some(_) => format!(
+                "{} - 搜尋 {} - 第{}頁",
+                state.name,
+                state.input.value(),
+                state.page
+            ),
+            None => format!("{} - 第{}頁", state.name, state.page),
+        };
+        let title = match state.filter.sort {
+            BoardSort::LatestReply => title,
+            BoardSort::NewestPost => format!("{} - 最新發文", title),
+        };
+
         let block = Block::default()
-            .title(format!("{} - 第{}頁", state.name, state.page))
+            .title(title)
             .title_alignment(Alignment::Center)
             .borders(Borders::ALL);
 
-        let items: Vec<ListItem> = state
-            .items
-            .iter()
-            .map(|item| ListItem::new(vec![Line::from(item.title.as_ref())]))
-            .collect();
+        let items: Vec<ListItem> = state.items.iter().map(post_item).collect();
 
-        let selected_style = Style::default().add_modifier(Modifier::REVERSED);
         let list = List::new(items)
             .block(block)
             .highlight_style(selected_style);
-        StatefulWidget::render(list, layout[0], buf, &mut state.state);
+        StatefulWidget::render(list, layout[1], buf, &mut state.state);
+
+        // search input, `keyword @author`
+        if state.mode == InputMode::Edit {
+            let value = state.input.value();
+            let width = layout[2].width.max(2) - 2;
+            let scroll = state.input.visual_scroll(width as usize);
+            Paragraph::new(format!("/{}", value))
+                .style(Style::default().fg(Color::Yellow))
+                .scroll((0, scroll as u16))
+                .render(layout[2], buf);
+
+            state.cursor((
+                layout[2].x + ((state.input.visual_cursor()).max(scroll) - scroll) as u16 + 1,
+                layout[2].y,
+            ));
+            return;
+        }
 
         Block::default()
             .title(format!("<- {} / {} ->", state.page, state.last_page))
             .title_alignment(Alignment::Center)
-            .render(layout[1], buf);
+            .render(layout[2], buf);
+    }
+}
+
+/// Pinned posts are highlighted, locked, digest and deleted ones are tagged.
+fn post_item(post: &BoardPost) -> ListItem<'_> {
+    let tag_style = Style::default().fg(Color::Magenta);
+    let flags = &post.flags;
+    let mut spans = vec![];
+
+    [
+        (flags.pinned, "[置頂] "),
+        (flags.digest, "[精華] "),
+        (flags.locked, "[鎖定] "),
+    ]
+    .into_iter()
+    .filter(|(on, _)| *on)
+    .for_each(|(_, tag)| spans.push(Span::styled(tag, tag_style)));
+
+    let title_style = if flags.deleted {
+        Style::default()
+            .fg(Color::DarkGray)
+            .add_modifier(Modifier::CROSSED_OUT)
+    } else if flags.pinned {
+        Style::default().fg(Color::Yellow)
+    } else {
+        Style::default()
+    };
+    spans.push(Span::styled(post.title.as_str(), title_style));
+
+    ListItem::new(Line::from(spans))
+}
+
+/// Panel with the description, moderators and stats of the board.
+pub struct BoardInfoUI<'a>(pub &'a BoardInfo);
+
+impl Widget for BoardInfoUI<'_> {
+    fn render(self, area: ratatui::layout::Rect, buf: &mut ratatui::buffer::Buffer) {
+        let info = self.0;
+        let label = Style::default().fg(Color::DarkGray);
+        let field = |name: &'static str, value: String| {
+            Line::from(vec![Span::styled(name, label), Span::raw(value)])
+        };
+
+        let moderators = info
+            .moderators
+            .iter()
+            .map(|m| format!("{} ({})", m.name, m.id))
+            .collect::<Vec<String>>()
+            .join(", ");
+
+        let mut lines = vec![
+            field("版主: ", moderators),
+            field("人氣: ", info.popularity.to_string()),
+            field("訂閱: ", info.subscribers.to_string()),
+        ];
+        if let Some(rules) = &info.rules {
+            lines.push(field("版規: ", rules.to_owned()));
+        }
+        if let Some(icon) = &info.icon {
+            lines.push(field("圖示: ", icon.to_owned()));
+        }
+        lines.push(Line::default());
+        lines.push(Line::from(info.desc.as_ref()));
+
+        Clear.render(area, buf);
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .block(
+                Block::default()
+                    .title(format!("{} - 看板資訊", info.name))
+                    .title_alignment(Alignment::Center)
+                    .borders(Borders::ALL),
+            )
+            .render(area, buf);
     }
 }
@@ -1,8 +1,9 @@
+use bahamut::api::time;
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
     style::{Color, Modifier, Style},
-    text::{Span, Line},
+    text::{Line, Span},
     widgets::{Block, Paragraph, StatefulWidget, Widget, Wrap},
 };
 
@@ -18,16 +19,31 @@ impl StatefulWidget for CommentPageUI {
 
         let name_style = Style::default().add_modifier(Modifier::REVERSED);
         let floor_style = Style::default().fg(Color::White);
+        let top_style = Style::default().fg(Color::Yellow);
+        let time_style = Style::default().fg(Color::DarkGray);
+        let now = time::now();
         let items: Vec<Line> = state
             .items
             .iter()
             .skip(state.offset)
-            .flat_map(|comment| {
+            .enumerate()
+            .flat_map(|(i, comment)| {
+                // the comment on top is the one `u` opens the author of
+                let floor_style = if i == 0 { top_style } else { floor_style };
                 vec![
                     Line::from(vec![
                         Span::styled::<String>(format!("B{} ", comment.floor), floor_style),
                         Span::styled::<&str>(comment.nick.as_ref(), name_style),
-                        Span::from(format!(": {}", comment.content)),
+                        Span::from(format!(": {} ", comment.content)),
+                        Span::styled(
+                            format!(
+                                "GP {} BP {} {}",
+                                comment.gp,
+                                comment.bp,
+                                comment.wtime.relative_to(now)
+                            ),
+                            time_style,
+                        ),
                     ]),
                     Line::from(""),
                 ]
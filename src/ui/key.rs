@@ -1,9 +1,10 @@
 use std::{cell::RefCell, rc::Rc, sync::mpsc::Sender};
 
+use bahamut::api::{post::Vote, search::SearchResult};
 use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
 use tui_input::backend::crossterm::EventHandler;
 
-use crate::channel::DataRequestMsg;
+use crate::channel::{BoardFilter, DataRequestMsg};
 
 use super::state::{AppState, CursorMoveable, InputMode, Page};
 
@@ -24,6 +25,8 @@ pub fn handle_key(app: &mut AppState, event: KeyEvent, tx: Sender<DataRequestMsg
         return KeyBindEvent::None;
     }
 
+    app.status = None;
+
     if handle_general_key(app, event, tx.clone()).is_quit() {
         return KeyBindEvent::Quit;
     }
@@ -34,9 +37,11 @@ pub fn handle_key(app: &mut AppState, event: KeyEvent, tx: Sender<DataRequestMsg
 
     match app.page {
         Page::Search => handle_search_key(app, event, tx),
+        Page::MyBoards => handle_my_boards_key(app, event, tx),
         Page::Board => handle_board_key(app, event, tx),
         Page::Post => handle_post_key(app, event, tx),
         Page::Comment => handle_comment_key(app, event, tx),
+        Page::User => handle_user_key(app, event, tx),
     }
 }
 
@@ -56,12 +61,15 @@ fn handle_general_key(
     }
 
     if let KeyCode::Char('q') = event.code {
-        if app.search.mode != InputMode::Edit {
+        if !app.is_editing() {
             match app.page {
                 Page::Search => return KeyBindEvent::Quit,
-                Page::Board => app.page = Page::Search,
-                Page::Post => app.page = Page::Board,
+                Page::MyBoards => app.page = Page::Search,
+                Page::Board if app.board.show_info => app.board.show_info = false,
+                Page::Board => app.page = app.board.back,
+                Page::Post => app.page = app.post.back,
                 Page::Comment => app.page = Page::Post,
+                Page::User => app.page = app.user.back,
             }
         }
     };
@@ -76,18 +84,37 @@ fn handle_search_key(
 ) -> KeyBindEvent {
     match app.search.mode {
         InputMode::Normal => match event.code {
+            KeyCode::Char('j') | KeyCode::Down if app.search.hot_focus => app.search.hot_next(),
+            KeyCode::Char('k') | KeyCode::Up if app.search.hot_focus => app.search.hot_previous(),
             KeyCode::Char('j') | KeyCode::Down => app.search.next(),
             KeyCode::Char('k') | KeyCode::Up => app.search.previous(),
             KeyCode::Char('a' | 'e' | 'i' | 'o') => app.search.mode(InputMode::Edit),
+            KeyCode::Tab => app.search.toggle_focus(),
+            KeyCode::Enter if app.search.hot_focus => {
+                if let Some(post) = app.search.selected_hot_post() {
+                    let url = post.url.to_string();
+                    let board_id = post.category.id.id.to_owned();
+                    app.loading = true;
+                    app.board.id(board_id);
+                    app.post.url = url.to_owned();
+                    app.post.back(Page::Search);
+                    tx.send(DataRequestMsg::PostPage(url, 1, true))
+                        .unwrap_or(());
+                }
+            }
+            KeyCode::Char('m') => {
+                app.loading = true;
+                tx.send(DataRequestMsg::MyBoards).unwrap_or(());
+            }
             KeyCode::Enter => {
-                if let Some(i) = app.search.state.selected() {
-                    if let Some(board) = app.search.items.get(i) {
-                        app.loading = true;
-                        app.board.name(board.name.to_owned());
-                        app.board.id(board.id.to_owned());
-                        tx.send(DataRequestMsg::BoardPage(board.id.to_string(), 1, true))
-                            .map_or((), |_| ());
-                    }
+                let board = app
+                    .search
+                    .state
+                    .selected()
+                    .and_then(|i| app.search.items.get(i))
+                    .cloned();
+                if let Some(board) = board {
+                    open_board(app, &board, tx);
                 }
             }
             _ => (),
@@ -100,7 +127,7 @@ fn handle_search_key(
                 if !value.is_empty() {
                     app.loading = true;
                     tx.send(DataRequestMsg::SearchResult(value.into()))
-                        .map_or((), |_| ());
+                        .unwrap_or(());
                 }
             }
             _ => {
@@ -112,12 +139,100 @@ fn handle_search_key(
     KeyBindEvent::None
 }
 
+fn handle_my_boards_key(
+    app: &mut AppState,
+    event: KeyEvent,
+    tx: Sender<DataRequestMsg>,
+) -> KeyBindEvent {
+    match event.code {
+        KeyCode::Char('j') | KeyCode::Down => app.my_boards.next(),
+        KeyCode::Char('k') | KeyCode::Up => app.my_boards.previous(),
+        KeyCode::Char('r') => {
+            app.loading = true;
+            tx.send(DataRequestMsg::MyBoards).unwrap_or(());
+        }
+        KeyCode::Enter => {
+            let board = app
+                .my_boards
+                .state
+                .selected()
+                .and_then(|i| app.my_boards.items.get(i))
+                .cloned();
+            if let Some(board) = board {
+                open_board(app, &board, tx);
+            }
+        }
+        _ => (),
+    }
+
+    KeyBindEvent::None
+}
+
+/// Load the first page of `board` with no filter.
+fn open_board(app: &mut AppState, board: &SearchResult, tx: Sender<DataRequestMsg>) {
+    app.loading = true;
+    app.board.back(app.page);
+    app.board.name(board.name.to_owned());
+    app.board.id(board.id.to_owned());
+    app.board.filter = BoardFilter::default();
+    app.board.categories = vec![];
+    tx.send(DataRequestMsg::BoardPage(
+        board.id.to_string(),
+        1,
+        true,
+        BoardFilter::default(),
+    ))
+    .unwrap_or(());
+}
+
 fn handle_board_key(
     app: &mut AppState,
     event: KeyEvent,
     tx: Sender<DataRequestMsg>,
 ) -> KeyBindEvent {
+    if app.board.mode == InputMode::Edit {
+        match event.code {
+            KeyCode::Esc => app.board.mode(InputMode::Normal),
+            KeyCode::Enter => {
+                app.board.mode(InputMode::Normal);
+                app.board.filter.query = app.board.query_from_input();
+                app.loading = true;
+                tx.send(DataRequestMsg::BoardPage(
+                    app.board.id.to_owned(),
+                    1,
+                    true,
+                    app.board.filter.clone(),
+                ))
+                .unwrap_or(());
+            }
+            _ => {
+                app.board.input.handle_event(&Event::Key(event));
+            }
+        }
+
+        return KeyBindEvent::None;
+    }
+
+    // the info panel takes every key until it's closed, `q` is handled as a
+    // general key
+    if app.board.show_info {
+        if let KeyCode::Esc | KeyCode::Char('i') = event.code {
+            app.board.show_info = false;
+        }
+
+        return KeyBindEvent::None;
+    }
+
     match event.code {
+        KeyCode::Char('i') => {
+            if app.board.has_info() {
+                app.board.show_info = true;
+            } else {
+                app.loading = true;
+                tx.send(DataRequestMsg::BoardInfo(app.board.id.to_owned()))
+                    .unwrap_or(());
+            }
+        }
         KeyCode::Char('j') | KeyCode::Down => app.board.next(),
         KeyCode::Char('k') | KeyCode::Up => app.board.previous(),
         KeyCode::Char('h') | KeyCode::Left => {
@@ -129,8 +244,9 @@ fn handle_board_key(
                     app.board.id.to_owned(),
                     app.board.page - 1,
                     true,
+                    app.board.filter.clone(),
                 ))
-                .map_or((), |_| ())
+                .unwrap_or(())
             }
         }
         KeyCode::Char('l') | KeyCode::Right => {
@@ -142,8 +258,9 @@ fn handle_board_key(
                     app.board.id.to_owned(),
                     app.board.page + 1,
                     true,
+                    app.board.filter.clone(),
                 ))
-                .map_or((), |_| ())
+                .unwrap_or(())
             }
         }
         KeyCode::Enter => {
@@ -151,8 +268,9 @@ fn handle_board_key(
                 if let Some(post) = app.board.items.get(v) {
                     app.loading = true;
                     app.post.url = post.url.to_string();
+                    app.post.back(Page::Board);
                     tx.send(DataRequestMsg::PostPage(post.url.to_string(), 1, true))
-                        .map_or((), |_| ())
+                        .unwrap_or(())
                 }
             }
         }
@@ -162,8 +280,26 @@ fn handle_board_key(
                 app.board.id.to_owned(),
                 app.board.page,
                 false,
+                app.board.filter.clone(),
+            ))
+            .unwrap_or(())
+        }
+        KeyCode::Char('/') => app.board.mode(InputMode::Edit),
+        KeyCode::Char('p') => app.board.toggle_pinned(),
+        KeyCode::Char('s') | KeyCode::Char('c') => {
+            if event.code == KeyCode::Char('s') {
+                app.board.toggle_sort();
+            } else {
+                app.board.next_category();
+            }
+            app.loading = true;
+            tx.send(DataRequestMsg::BoardPage(
+                app.board.id.to_owned(),
+                1,
+                true,
+                app.board.filter.clone(),
             ))
-            .map_or((), |_| ())
+            .unwrap_or(())
         }
         _ => (),
     }
@@ -176,6 +312,29 @@ fn handle_post_key(
     event: KeyEvent,
     tx: Sender<DataRequestMsg>,
 ) -> KeyBindEvent {
+    if app.post.mode == InputMode::Edit {
+        match event.code {
+            KeyCode::Esc => app.post.mode(InputMode::Normal),
+            KeyCode::Enter => {
+                let floor = app.post.input.value().trim().parse::<u16>().ok();
+                app.post.mode(InputMode::Normal);
+                if let Some(floor) = floor {
+                    if !app.post.jump_to_floor(floor) {
+                        app.loading = true;
+                        tx.send(DataRequestMsg::PostFloor(app.post.url.to_owned(), floor))
+                            .unwrap_or(());
+                    }
+                }
+            }
+            KeyCode::Char(c) if !c.is_ascii_digit() => (),
+            _ => {
+                app.post.input.handle_event(&Event::Key(event));
+            }
+        }
+
+        return KeyBindEvent::None;
+    }
+
     let app = Rc::new(RefCell::new(app));
     let next = |app: &Rc<RefCell<&mut AppState>>| {
         let mut app = app.borrow_mut();
@@ -186,7 +345,7 @@ fn handle_post_key(
                 app.post.page + 1,
                 false,
             ))
-            .map_or((), |_| ());
+            .unwrap_or(());
         }
     };
 
@@ -195,6 +354,17 @@ fn handle_post_key(
         KeyCode::PageDown => next(&app),
         KeyCode::PageUp => app.borrow_mut().post.previous(),
         KeyCode::Home => app.borrow_mut().post.first(),
+        KeyCode::Char('g') => app.borrow_mut().post.mode(InputMode::Edit),
+        KeyCode::Char('f') if event.modifiers != KeyModifiers::CONTROL => {
+            let mut app = app.borrow_mut();
+            let quoted = app
+                .post
+                .current()
+                .and_then(|c| c.replies_to.last().copied());
+            if let Some(floor) = quoted {
+                app.post.jump_to_floor(floor);
+            }
+        }
         KeyCode::Char('j') | KeyCode::Down => app.borrow_mut().post.scroll_down(),
         KeyCode::Char('k') | KeyCode::Up => app.borrow_mut().post.scroll_up(),
         KeyCode::Char('o') => {
@@ -206,7 +376,31 @@ fn handle_post_key(
                     app.board.id.to_owned(),
                     content.id.to_owned(),
                 ))
-                .map_or((), |_| ());
+                .unwrap_or(());
+            }
+        }
+        KeyCode::Char(c @ ('+' | '-')) => {
+            let vote = if c == '+' { Vote::Gp } else { Vote::Bp };
+            let mut app = app.borrow_mut();
+            let floor = app.post.current().map(|c| (c.id.to_owned(), c.floor));
+            if let Some((c_id, floor)) = floor {
+                app.loading = true;
+                tx.send(DataRequestMsg::VoteFloor(
+                    app.board.id.to_owned(),
+                    c_id,
+                    floor,
+                    vote,
+                ))
+                .unwrap_or(());
+            }
+        }
+        KeyCode::Char('u') => {
+            let mut app = app.borrow_mut();
+            let id = app.post.current().map(|c| c.user.id.to_owned());
+            if let Some(id) = id.filter(|id| !id.is_empty()) {
+                app.loading = true;
+                app.user.back(Page::Post);
+                tx.send(DataRequestMsg::UserProfile(id)).unwrap_or(());
             }
         }
         KeyCode::Char('r') => {
@@ -217,7 +411,7 @@ fn handle_post_key(
                 app.post.page,
                 false,
             ))
-            .map_or((), |_| ())
+            .unwrap_or(())
         }
         _ => (),
     };
@@ -243,11 +437,38 @@ fn handle_post_key(
 fn handle_comment_key(
     app: &mut AppState,
     event: KeyEvent,
-    _: Sender<DataRequestMsg>,
+    tx: Sender<DataRequestMsg>,
 ) -> KeyBindEvent {
     match event.code {
         KeyCode::Char('j') | KeyCode::Down => app.comment.next(),
         KeyCode::Char('k') | KeyCode::Up => app.comment.previous(),
+        // votes and author of the comment on top
+        KeyCode::Char(c @ ('+' | '-')) => {
+            let vote = if c == '+' { Vote::Gp } else { Vote::Bp };
+            if let Some(comment) = app.comment.items.get(app.comment.offset) {
+                app.loading = true;
+                tx.send(DataRequestMsg::VoteComment(Box::new(comment.clone()), vote))
+                    .unwrap_or(());
+            }
+        }
+        KeyCode::Char('u') => {
+            if let Some(comment) = app.comment.items.get(app.comment.offset) {
+                app.loading = true;
+                app.user.back(Page::Comment);
+                tx.send(DataRequestMsg::UserProfile(comment.userid.to_owned()))
+                    .unwrap_or(());
+            }
+        }
+        _ => (),
+    };
+
+    KeyBindEvent::None
+}
+
+fn handle_user_key(app: &mut AppState, event: KeyEvent, _: Sender<DataRequestMsg>) -> KeyBindEvent {
+    match event.code {
+        KeyCode::Char('j') | KeyCode::Down => app.user.next(),
+        KeyCode::Char('k') | KeyCode::Up => app.user.previous(),
         _ => (),
     };
 
@@ -2,23 +2,29 @@ pub mod board;
 pub mod comment;
 pub mod key;
 pub mod loading;
+pub mod my_boards;
 pub mod post;
 pub mod search;
 pub mod state;
+pub mod user;
 
 use ratatui::{
     backend::Backend,
     layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Style},
+    widgets::{Clear, Paragraph},
     Frame,
 };
 
 use self::{
-    board::BoardPageUI,
+    board::{BoardInfoUI, BoardPageUI},
     comment::CommentPageUI,
     loading::Loading,
+    my_boards::MyBoardsPageUI,
     post::PostPageUI,
     search::SearchPageUI,
     state::{AppState, InputMode, Page},
+    user::UserPageUI,
 };
 
 pub fn ui<B: Backend>(f: &mut Frame<B>, app: &mut AppState) {
@@ -31,17 +37,41 @@ pub fn ui<B: Backend>(f: &mut Frame<B>, app: &mut AppState) {
                 f.set_cursor(app.search.cursor.0, app.search.cursor.1);
             }
         }
+        Page::MyBoards => {
+            f.render_stateful_widget(MyBoardsPageUI, size, &mut app.my_boards);
+        }
         Page::Board => {
             f.render_stateful_widget(BoardPageUI, size, &mut app.board);
+            if app.board.mode == InputMode::Edit {
+                f.set_cursor(app.board.cursor.0, app.board.cursor.1);
+            }
+            if let Some(info) = app.board.info.as_ref().filter(|_| app.board.show_info) {
+                f.render_widget(BoardInfoUI(info), centered_rect(70, 60, size));
+            }
         }
         Page::Post => {
             f.render_stateful_widget(PostPageUI, size, &mut app.post);
+            if app.post.mode == InputMode::Edit {
+                f.set_cursor(app.post.cursor.0, app.post.cursor.1);
+            }
         }
         Page::Comment => {
             f.render_stateful_widget(CommentPageUI, size, &mut app.comment);
         }
+        Page::User => {
+            f.render_stateful_widget(UserPageUI, size, &mut app.user);
+        }
     };
 
+    if let Some(status) = app.status.as_ref().filter(|_| size.height > 0) {
+        let area = Rect::new(size.x, size.bottom() - 1, size.width, 1);
+        f.render_widget(Clear, area);
+        f.render_widget(
+            Paragraph::new(status.as_str()).style(Style::default().fg(Color::Yellow)),
+            area,
+        );
+    }
+
     if app.loading {
         let y = if size.height < 18 {
             25
@@ -0,0 +1,44 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Rect},
+    style::{Color, Modifier, Style},
+    text::Line,
+    widgets::{Block, Borders, List, ListItem, Paragraph, StatefulWidget, Widget},
+};
+
+use super::state::MyBoardsState;
+
+pub struct MyBoardsPageUI;
+
+impl StatefulWidget for MyBoardsPageUI {
+    type State = MyBoardsState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let block = Block::default()
+            .title("我的看板")
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL);
+
+        if let Some(error) = &state.error {
+            Paragraph::new(error.as_str())
+                .style(Style::default().fg(Color::Yellow))
+                .alignment(Alignment::Center)
+                .block(block)
+                .render(area, buf);
+            return;
+        }
+
+        let items: Vec<ListItem> = state
+            .items
+            .iter()
+            .map(|item| ListItem::new(vec![Line::from(item.name.as_ref())]))
+            .collect();
+
+        let selected_style = Style::default().add_modifier(Modifier::REVERSED);
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(selected_style);
+
+        StatefulWidget::render(list, area, buf, &mut state.state);
+    }
+}
@@ -1,12 +1,16 @@
-use bahamut::api::post::PostContent;
+use bahamut::api::{
+    post::{self, Inline, PostContent, TextStyle},
+    time,
+};
 use ratatui::{
     buffer::Buffer,
     layout::{Constraint, Direction, Layout, Rect},
-    text::Line,
-    widgets::{Block, Borders, Paragraph, StatefulWidget, Widget, Wrap},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, StatefulWidget, Widget, Wrap},
 };
 
-use super::state::PostPageState;
+use super::state::{InputMode, PostPageState};
 
 #[derive(Default)]
 pub struct PostPageUI;
@@ -42,22 +46,156 @@ impl StatefulWidget for PostPageUI {
 
         Paragraph::new(vec![
             Line::from(state.data.title.as_ref()),
-            Line::from(format!("{}樓", current.floor)),
-            Line::from(current.date.as_ref()),
+            Line::from(format!(
+                "{}樓 GP {} BP {}",
+                current.floor, current.gp, current.bp
+            )),
+            Line::from(current.date.relative_to(time::now())),
+            reply_line(current, state.data.replies_of(current.floor).len()),
         ])
         .block(Block::default().borders(Borders::ALL))
         .render(top[1], buf);
 
         // desc
-        let desc: Vec<Line> = current
-            .desc
-            .iter()
+        let desc: Vec<Line> = desc_lines(&current.desc)
+            .into_iter()
             .skip(state.scroll_offset)
-            .map(|s| Line::from(s.to_owned()))
             .collect();
         Paragraph::new(desc)
             .wrap(Wrap { trim: false })
             .block(Block::default().borders(Borders::ALL))
             .render(layout[1], buf);
+
+        // floor prompt on the last line of the content
+        if state.mode == InputMode::Edit && layout[1].height > 2 {
+            let label = "跳到樓層: ";
+            let prompt = Rect {
+                x: layout[1].x + 1,
+                y: layout[1].y + layout[1].height - 2,
+                width: layout[1].width.saturating_sub(2),
+                height: 1,
+            };
+
+            Clear.render(prompt, buf);
+            Paragraph::new(format!("{}{}", label, state.input.value()))
+                .style(Style::default().fg(Color::Yellow))
+                .render(prompt, buf);
+
+            let label_width = Span::raw(label).width() as u16;
+            state.cursor((
+                prompt.x + label_width + state.input.visual_cursor() as u16,
+                prompt.y,
+            ));
+        }
+    }
+}
+
+/// `回覆 #3 #5 · 被回覆 2 次`
+fn reply_line(current: &PostContent, replied: usize) -> Line<'static> {
+    let mut text = current
+        .replies_to
+        .iter()
+        .map(|floor| format!("#{}", floor))
+        .collect::<Vec<String>>()
+        .join(" ");
+
+    if !text.is_empty() {
+        text = format!("回覆 {}", text);
     }
+
+    if replied > 0 {
+        if !text.is_empty() {
+            text.push_str(" · ");
+        }
+        text.push_str(format!("被回覆 {} 次", replied).as_ref());
+    }
+
+    Line::styled(text, Style::default().fg(Color::DarkGray))
+}
+
+/// Lines shown for the content of a floor, also used to know how far it scrolls.
+pub fn desc_lines(desc: &[post::Block]) -> Vec<Line<'static>> {
+    desc.iter().flat_map(block_lines).collect()
+}
+
+fn block_lines(block: &post::Block) -> Vec<Line<'static>> {
+    let tag_style = Style::default().fg(Color::Magenta);
+
+    match block {
+        post::Block::Paragraph(inlines) => {
+            let mut lines = vec![Line::default()];
+            inline_spans(inlines, Style::default(), &mut lines);
+            lines
+        }
+        post::Block::Image(img) => vec![Line::from(vec![
+            Span::styled("[圖片] ", tag_style),
+            Span::from(img.original.to_owned()),
+        ])],
+        post::Block::Video(url) => vec![Line::from(vec![
+            Span::styled("[影片] ", tag_style),
+            Span::from(url.to_owned()),
+        ])],
+        post::Block::Quote(blocks) => prefixed(desc_lines(blocks), "│ "),
+        post::Block::Spoiler(blocks) => {
+            let mut lines = vec![Line::styled("[防雷]", tag_style)];
+            lines.extend(prefixed(desc_lines(blocks), "┆ "));
+            lines
+        }
+        post::Block::Rule => vec![Line::from("─".repeat(20))],
+    }
+}
+
+fn inline_spans(inlines: &[Inline], base: Style, lines: &mut Vec<Line<'static>>) {
+    for inline in inlines {
+        match inline {
+            Inline::Text(text, style) => {
+                let span = Span::styled(text.to_owned(), text_style(base, style));
+                if let Some(line) = lines.last_mut() {
+                    line.spans.push(span);
+                }
+            }
+            Inline::Link { href, children } => {
+                let link_style = base.fg(Color::Cyan).add_modifier(Modifier::UNDERLINED);
+                inline_spans(children, link_style, lines);
+
+                let text = children.iter().map(Inline::text).collect::<String>();
+                if text.trim() != href {
+                    let href =
+                        Span::styled(format!(" ({})", href), Style::default().fg(Color::DarkGray));
+                    if let Some(line) = lines.last_mut() {
+                        line.spans.push(href);
+                    }
+                }
+            }
+            Inline::Break => lines.push(Line::default()),
+        }
+    }
+}
+
+fn text_style(base: Style, style: &TextStyle) -> Style {
+    let mut base = base;
+    if style.bold {
+        base = base.add_modifier(Modifier::BOLD);
+    }
+    if style.italic {
+        base = base.add_modifier(Modifier::ITALIC);
+    }
+    if style.underline {
+        base = base.add_modifier(Modifier::UNDERLINED);
+    }
+    if style.strike {
+        base = base.add_modifier(Modifier::CROSSED_OUT);
+    }
+    base
+}
+
+fn prefixed(lines: Vec<Line<'static>>, prefix: &'static str) -> Vec<Line<'static>> {
+    let quote_style = Style::default().fg(Color::DarkGray);
+    lines
+        .into_iter()
+        .map(|mut line| {
+            line.spans.insert(0, Span::styled(prefix, quote_style));
+            line
+        })
+        .collect()
 }
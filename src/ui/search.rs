@@ -1,6 +1,6 @@
 use ratatui::{
     buffer::Buffer,
-    layout::{Constraint, Layout, Rect},
+    layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::Line,
     widgets::{Block, Borders, List, ListItem, Paragraph, StatefulWidget, Widget},
@@ -51,20 +51,49 @@ impl StatefulWidget for SearchPageUI {
             layout[0].y + 1,
         ));
 
-        // search result
+        // search result, or the hot boards and threads next to each other
+        let lists = match state.is_hot() {
+            true => Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+                .split(layout[1]),
+            false => Layout::default()
+                .constraints([Constraint::Min(0)])
+                .split(layout[1]),
+        };
+
         let items: Vec<ListItem> = state
             .items
             .iter()
             .map(|item| ListItem::new(vec![Line::from(item.name.as_ref())]))
             .collect();
 
-        let block = Block::default().borders(Borders::ALL);
-
-        let selected_style = Style::default().add_modifier(Modifier::REVERSED);
+        let title = if state.is_hot() { "熱門看板" } else { "" };
         let list = List::new(items)
-            .block(block)
-            .highlight_style(selected_style);
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .highlight_style(highlight_style(!state.hot_focus));
+        StatefulWidget::render(list, lists[0], buf, &mut state.state);
+
+        if state.is_hot() {
+            let posts: Vec<ListItem> = state
+                .hot_posts
+                .iter()
+                .map(|post| ListItem::new(vec![Line::from(post.title.as_ref())]))
+                .collect();
+
+            let list = List::new(posts)
+                .block(Block::default().borders(Borders::ALL).title("熱門文章"))
+                .highlight_style(highlight_style(state.hot_focus));
+            StatefulWidget::render(list, lists[1], buf, &mut state.hot_state);
+        }
+    }
+}
 
-        StatefulWidget::render(list, layout[1], buf, &mut state.state);
+/// Only the focused list shows its selection reversed.
+fn highlight_style(focused: bool) -> Style {
+    if focused {
+        Style::default().add_modifier(Modifier::REVERSED)
+    } else {
+        Style::default().add_modifier(Modifier::BOLD)
     }
 }
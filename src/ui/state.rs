@@ -1,11 +1,16 @@
 use bahamut::api::{
-    board::BoardPost,
+    board::{BoardCategory, BoardInfo, BoardPost, BoardPostQuery, BoardSort},
     post::{Post, PostComment, PostContent},
     search::SearchResult,
+    user::UserProfile,
 };
 use ratatui::widgets::ListState;
 use tui_input::Input;
 
+use crate::channel::BoardFilter;
+
+use super::post::desc_lines;
+
 #[derive(Clone, PartialEq, Default)]
 pub enum InputMode {
     #[default]
@@ -13,11 +18,15 @@ pub enum InputMode {
     Edit,
 }
 
+#[derive(Clone, Copy, Default)]
 pub enum Page {
+    #[default]
     Search,
+    MyBoards,
     Board,
     Post,
     Comment,
+    User,
 }
 
 pub trait CursorMoveable {
@@ -68,16 +77,27 @@ pub trait ListStateInit<T> {
 pub struct AppState {
     pub page: Page,
     pub search: SearchPageState,
+    pub my_boards: MyBoardsState,
     pub board: BoardPageState,
     pub post: PostPageState,
     pub comment: PostCommentState,
+    pub user: UserPageState,
     pub loading: bool,
+    /// Result of the last action, shown until the next key
+    pub status: Option<String>,
 }
 
 impl AppState {
     pub fn new() -> AppState {
         AppState::default()
     }
+
+    /// Some input has the focus, keys are typed instead of bound.
+    pub fn is_editing(&self) -> bool {
+        self.search.mode == InputMode::Edit
+            || self.board.mode == InputMode::Edit
+            || self.post.mode == InputMode::Edit
+    }
 }
 
 impl Default for AppState {
@@ -85,10 +105,13 @@ impl Default for AppState {
         AppState {
             page: Page::Search,
             search: SearchPageState::default(),
+            my_boards: MyBoardsState::default(),
             board: BoardPageState::default(),
             post: PostPageState::default(),
             comment: PostCommentState::default(),
+            user: UserPageState::default(),
             loading: false,
+            status: None,
         }
     }
 }
@@ -96,15 +119,61 @@ impl Default for AppState {
 #[derive(Default, Clone)]
 pub struct SearchPageState {
     pub state: ListState,
+    /// Search results, or the hot boards before searching
     pub items: Vec<SearchResult>,
     pub mode: InputMode,
     pub input: Input,
     pub cursor: (u16, u16),
+    /// Hot threads of the front page, shown until the first search
+    pub hot_posts: Vec<BoardPost>,
+    pub hot_state: ListState,
+    /// The hot threads have the focus instead of the boards
+    pub hot_focus: bool,
 }
 
 impl SearchPageState {
     pub fn items(&mut self, items: Vec<SearchResult>) {
         self.items = items;
+        self.hot_posts = vec![];
+        self.hot_focus = false;
+    }
+
+    /// Front page rankings, kept until a search replaces them.
+    pub fn hot(&mut self, boards: Vec<SearchResult>, posts: Vec<BoardPost>) {
+        self.items = boards;
+        self.hot_posts = posts;
+        self.hot_state
+            .select(Some(0).filter(|_| !self.hot_posts.is_empty()));
+    }
+
+    pub fn is_hot(&self) -> bool {
+        !self.hot_posts.is_empty()
+    }
+
+    pub fn toggle_focus(&mut self) {
+        self.hot_focus = self.is_hot() && !self.hot_focus;
+    }
+
+    pub fn hot_next(&mut self) {
+        let max = self.hot_posts.len();
+        if max > 0 {
+            let i = self.hot_state.selected().map_or(0, |i| (i + 1) % max);
+            self.hot_state.select(Some(i));
+        }
+    }
+
+    pub fn hot_previous(&mut self) {
+        let max = self.hot_posts.len();
+        if max > 0 {
+            let i = self.hot_state.selected().map_or(0, |i| (i + max - 1) % max);
+            self.hot_state.select(Some(i));
+        }
+    }
+
+    pub fn selected_hot_post(&self) -> Option<&BoardPost> {
+        self.hot_state
+            .selected()
+            .and_then(|i| self.hot_posts.get(i))
     }
 
     pub fn mode(&mut self, mode: InputMode) {
@@ -148,27 +217,175 @@ impl CursorMoveable for SearchPageState {
     }
 }
 
+/// Boards subscribed by the logged in account.
+#[derive(Default)]
+pub struct MyBoardsState {
+    pub state: ListState,
+    pub items: Vec<SearchResult>,
+    /// Why the boards can't be loaded, such as not logged in
+    pub error: Option<String>,
+}
+
+impl MyBoardsState {
+    pub fn items(&mut self, items: Result<Vec<SearchResult>, String>) {
+        match items {
+            Ok(items) => {
+                self.items = items;
+                self.error = None;
+            }
+            Err(e) => {
+                self.items = vec![];
+                self.error = Some(e);
+            }
+        }
+    }
+}
+
+impl ListStateInit<SearchResult> for MyBoardsState {
+    fn lists(&self) -> &Vec<SearchResult> {
+        &self.items
+    }
+
+    fn state(&mut self) -> &mut ListState {
+        &mut self.state
+    }
+}
+
+impl CursorMoveable for MyBoardsState {
+    fn index(&self) -> usize {
+        self.state.selected().map_or(1, |x| x)
+    }
+
+    fn max(&self) -> usize {
+        self.items.len()
+    }
+
+    fn next(&mut self) {
+        if !self.items.is_empty() {
+            self.state.select(Some(self.next_index()))
+        }
+    }
+
+    fn previous(&mut self) {
+        if !self.items.is_empty() {
+            self.state.select(Some(self.previous_index()))
+        }
+    }
+}
+
 #[derive(Default)]
 pub struct BoardPageState {
+    /// Page the board is opened from
+    pub back: Page,
     pub state: ListState,
+    /// Posts shown in the list
     pub items: Vec<BoardPost>,
+    all_items: Vec<BoardPost>,
+    pub hide_pinned: bool,
     pub id: String,
     pub name: String,
     pub last_page: u16,
     pub page: u16,
+    pub categories: Vec<BoardCategory>,
+    pub filter: BoardFilter,
+    pub mode: InputMode,
+    pub input: Input,
+    pub cursor: (u16, u16),
+    pub info: Option<BoardInfo>,
+    pub show_info: bool,
 }
 
 impl BoardPageState {
+    pub fn back(&mut self, page: Page) {
+        self.back = page;
+    }
+
+    pub fn categories(&mut self, categories: Vec<BoardCategory>) {
+        // a filtered page may not list every category
+        if !categories.is_empty() {
+            self.categories = categories;
+        }
+    }
+
+    /// Next category of the board, back to the whole board after the last one.
+    pub fn next_category(&mut self) {
+        let current = self
+            .filter
+            .category
+            .as_ref()
+            .and_then(|id| self.categories.iter().position(|c| &c.id() == id));
+
+        let next = match current {
+            Some(i) => self.categories.get(i + 1),
+            None => self.categories.first(),
+        };
+
+        self.filter.category = next.map(|c| c.id());
+    }
+    pub fn mode(&mut self, mode: InputMode) {
+        self.mode = mode;
+    }
+
+    pub fn cursor(&mut self, cursor: (u16, u16)) {
+        self.cursor = cursor;
+    }
+
+    /// `keyword @author`, words starting with `@` are author ids.
+    pub fn query_from_input(&self) -> Option<BoardPostQuery> {
+        let (authors, words): (Vec<&str>, Vec<&str>) = self
+            .input
+            .value()
+            .split_whitespace()
+            .partition(|w| w.starts_with('@') && w.len() > 1);
+
+        let query = BoardPostQuery {
+            title: Some(words.join(" ")).filter(|t| !t.is_empty()),
+            author: authors
+                .first()
+                .map(|a| a.trim_start_matches('@').to_string()),
+        };
+
+        Some(query).filter(|q| !q.is_empty())
+    }
+
     pub fn id(&mut self, id: String) {
         self.id = id;
     }
 
+    /// Info of the current board, `false` if it has to be fetched first.
+    pub fn has_info(&self) -> bool {
+        self.info.as_ref().is_some_and(|info| info.id == self.id)
+    }
+
     pub fn name(&mut self, name: String) {
         self.name = name;
     }
 
     pub fn items(&mut self, items: Vec<BoardPost>) {
-        self.items = items;
+        self.all_items = items;
+        self.apply_hidden();
+    }
+
+    pub fn toggle_pinned(&mut self) {
+        self.hide_pinned = !self.hide_pinned;
+        self.apply_hidden();
+        self.init_select();
+    }
+
+    pub fn toggle_sort(&mut self) {
+        self.filter.sort = match self.filter.sort {
+            BoardSort::LatestReply => BoardSort::NewestPost,
+            BoardSort::NewestPost => BoardSort::LatestReply,
+        };
+    }
+
+    fn apply_hidden(&mut self) {
+        self.items = self
+            .all_items
+            .iter()
+            .filter(|post| !(self.hide_pinned && post.flags.pinned))
+            .cloned()
+            .collect();
     }
 
     pub fn last_page(&mut self, page: u16) {
@@ -211,15 +428,34 @@ impl CursorMoveable for BoardPageState {
 #[derive(Default)]
 pub struct PostPageState {
     pub data: Post,
+    /// Page to go back to, threads can be opened from the search page
+    pub back: Page,
     pub index: u16,
     pub page: u16,
     pub last_page: u16,
     pub url: String,
     pub scroll_offset: usize,
     pub scroll_size: usize,
+    /// Floor number prompt
+    pub mode: InputMode,
+    pub input: Input,
+    pub cursor: (u16, u16),
 }
 
 impl PostPageState {
+    pub fn back(&mut self, page: Page) {
+        self.back = page;
+    }
+
+    pub fn mode(&mut self, mode: InputMode) {
+        self.mode = mode;
+        self.input.reset();
+    }
+
+    pub fn cursor(&mut self, cursor: (u16, u16)) {
+        self.cursor = cursor;
+    }
+
     pub fn data(&mut self, data: Post) {
         self.data = data;
     }
@@ -280,6 +516,18 @@ impl PostPageState {
         self.data.posts.get(self.index as usize)
     }
 
+    /// Jump to a loaded floor, return false if it isn't loaded yet.
+    pub fn jump_to_floor(&mut self, floor: u16) -> bool {
+        match self.data.posts.iter().position(|post| post.floor == floor) {
+            Some(index) => {
+                self.scroll_offset = 0;
+                self.index = index as u16;
+                true
+            }
+            None => false,
+        }
+    }
+
     pub fn scroll_up(&mut self) {
         if self.scroll_offset > 0 {
             self.scroll_offset -= 1;
@@ -298,11 +546,11 @@ impl PostPageState {
 
     fn scrollable(&self) -> bool {
         if let Some(desc) = self.current() {
-            let desc = &desc.desc;
+            let lines = desc_lines(&desc.desc).len();
 
-            !desc.is_empty()
-                && desc.len() >= self.scroll_offset
-                && desc.len() - self.scroll_offset > self.scroll_size
+            lines > 0
+                && lines >= self.scroll_offset
+                && lines - self.scroll_offset > self.scroll_size
         } else {
             false
         }
@@ -345,3 +593,37 @@ impl PostCommentState {
         self.offset = 0;
     }
 }
+
+#[derive(Default)]
+pub struct UserPageState {
+    pub profile: UserProfile,
+    /// Page to go back to
+    pub back: Page,
+    pub offset: usize,
+    pub scroll_size: usize,
+}
+
+impl UserPageState {
+    pub fn profile(&mut self, profile: UserProfile) {
+        self.profile = profile;
+        self.offset = 0;
+    }
+
+    pub fn back(&mut self, page: Page) {
+        self.back = page;
+    }
+
+    pub fn scroll_size(&mut self, size: usize) {
+        self.scroll_size = size;
+    }
+
+    pub fn next(&mut self) {
+        if self.profile.posts.len() > self.offset + self.scroll_size {
+            self.offset += 1;
+        }
+    }
+
+    pub fn previous(&mut self) {
+        self.offset = self.offset.saturating_sub(1);
+    }
+}
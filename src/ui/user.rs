@@ -0,0 +1,75 @@
+use bahamut::api::time;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, Paragraph, StatefulWidget, Widget, Wrap},
+};
+
+use super::state::UserPageState;
+
+pub struct UserPageUI;
+
+impl StatefulWidget for UserPageUI {
+    type State = UserPageState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(8),
+                Constraint::Length(4),
+                Constraint::Min(0),
+            ])
+            .split(area);
+
+        let profile = &state.profile;
+        let label = Style::default().fg(Color::DarkGray);
+        let field = |name: &'static str, value: String| {
+            Line::from(vec![Span::styled(name, label), Span::raw(value)])
+        };
+
+        let joined = match profile.joined.is_parsed() {
+            true => format!(
+                "{} ({})",
+                profile.joined,
+                profile.joined.relative_to(time::now())
+            ),
+            false => profile.joined.to_string(),
+        };
+
+        Paragraph::new(vec![
+            field("稱號: ", profile.title.to_owned()),
+            field("等級: ", format!("lv.{}", profile.lv)),
+            field("GP: ", profile.gp.to_string()),
+            field("經驗: ", profile.exp.to_string()),
+            field("註冊: ", joined),
+            field("頭像: ", profile.avatar.to_owned()),
+        ])
+        .block(
+            Block::default()
+                .title(format!("{} ({})", profile.name, profile.id))
+                .borders(Borders::ALL),
+        )
+        .render(layout[0], buf);
+
+        Paragraph::new(profile.signature.as_str())
+            .wrap(Wrap { trim: true })
+            .block(Block::default().title("簽名").borders(Borders::ALL))
+            .render(layout[1], buf);
+
+        // borders take two lines
+        state.scroll_size(layout[2].height.saturating_sub(2) as usize);
+        let posts: Vec<ListItem> = state
+            .profile
+            .posts
+            .iter()
+            .skip(state.offset)
+            .map(|post| ListItem::new(post.title.as_str()))
+            .collect();
+
+        let list = List::new(posts).block(Block::default().title("最近文章").borders(Borders::ALL));
+        Widget::render(list, layout[2], buf);
+    }
+}